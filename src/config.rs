@@ -16,6 +16,7 @@ pub struct Config {
     loaded_core: bool,
     core_file: String,
     no_core: bool,
+    wrap: bool,
     pub run: bool,
 }
 
@@ -27,6 +28,7 @@ impl Config {
             loaded_core: false,
             core_file: DEFAULT_CORE[0].to_owned(),
             no_core: false,
+            wrap: false,
             run: true,
         }
     }
@@ -47,6 +49,7 @@ impl Config {
             .arg(arg!(-l --library <VALUE>).required(false))
             .arg(arg!(-f --file <VALUE>).required(false))
             .arg(arg!(-n - -nocore).required(false))
+            .arg(arg!(-w - -wrap).required(false))
             .get_matches();
 
         let debuglevel = arguments.get_one::<String>("debuglevel");
@@ -69,6 +72,11 @@ impl Config {
             self.no_core = *nc;
         }
 
+        let wrap = arguments.get_one::<bool>("wrap");
+        if let Some(w) = wrap {
+            self.wrap = *w;
+        }
+
         let file = arguments.get_one::<String>("file");
         if let Some(file) = file {
             self.loaded_file = file.clone();
@@ -83,6 +91,7 @@ impl Config {
         let mut forth = ForthInterpreter::new("Ok ", ">  ");
 
         forth.msg.set_level(self.debug_level.clone());
+        forth.set_wrap_arithmetic(self.wrap);
 
         if !self.no_core {
             for path in DEFAULT_CORE {
@@ -102,12 +111,10 @@ impl Config {
                 );
             }
         }
-        if self.loaded_file != "" {
-            if !forth.load_file(&self.loaded_file) {
-                forth
-                    .msg
-                    .error("MAIN", "Unable to load userfile", Some(&self.loaded_file));
-            }
+        if !self.loaded_file.is_empty() && !forth.load_file(&self.loaded_file) {
+            forth
+                .msg
+                .error("MAIN", "Unable to load userfile", Some(&self.loaded_file));
         }
 
         forth.set_abort_flag(false); // abort flag may have been set by load_file, but is no longer needed.
@@ -10,7 +10,7 @@
 : <> ( n -- n ) = 0= ;
 : min ( m n -- m | n ) 2dup < if drop else nip then ;
 : max ( m n -- m | n ) 2dup > if drop else nip then ;
-: abs (n -- n | -n ) dup 0 < if -1 * then ;
+: abs ( n -- n | -n ) dup 0 < if -1 * then ;
 : dbg-debug 3 dbg ;
 : dbg-info 2 dbg ;
 : dbg-warning 1 dbg ;
@@ -19,7 +19,6 @@
 : bl 32 ; ( puts the character code for a space on the stack )
 : 1- ( n -- n-1 ) 1 - ;
 : 1+ ( n -- n+1 ) 1 + ;
-: endif then ; ( synonym for then, to allow if - else - endif conditionals )
 
 : +! ( n addr -- ) dup @ rot + swap ! ;
 : ? ( addr -- ) @ . ;
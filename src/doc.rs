@@ -14,7 +14,6 @@ pub fn build_doc_strings() -> HashMap<String, String> {
         };
     }
 
-    doc!("+", "( j k -- j+k ) Push j+k on the stack");
     doc!("+", "( j k -- j+k ) Push j+k on the stack");
     doc!("-", "( j k -- j-k ) Push  -k on the stack");
     doc!("*", "( j k -- j*k ) Push j*k on the stack");
@@ -23,6 +22,7 @@ pub fn build_doc_strings() -> HashMap<String, String> {
     doc!("=", "( j k -- b ) If j == k push true else false");
     doc!("<", "( j k -- j/k ) If j < k push true else false");
     doc!("0<", "( j k -- j/k ) If j < 0 push true else false");
+    doc!("0=", "( j -- b ) If j == 0 push true else false");
     doc!(
         "true",
         "( -- -1 ) Push the canonical true value on the stack."
@@ -35,16 +35,88 @@ pub fn build_doc_strings() -> HashMap<String, String> {
         ".",
         "( n -- ) Pop the top of the stack and print it, followed by a newline"
     );
-    doc!(
-        "..",
-        "( n -- ) Pop the top of the stack and print it without a newline"
-    );
+    doc!("cr", "( -- ) Print a newline");
     doc!(
         "flush",
         "( -- ) Flush the stdout buffer. Required if no newline has been issued."
     );
-    doc!(".s", "( -- ) Print the contents of the calculation stack");
-    doc!(".s\"", "( -- ) Print the saved string to stdout");
+    doc!(
+        ".s",
+        "( -- ) Print the contents of the calculation stack, and the float stack if it is in use"
+    );
+    doc!(
+        "base",
+        "( -- addr ) The variable holding the base numbers are read and printed in"
+    );
+    doc!("decimal", "( -- ) Read and print numbers in base 10");
+    doc!("hex", "( -- ) Read and print numbers in base 16");
+    doc!("octal", "( -- ) Read and print numbers in base 8");
+    doc!("binary", "( -- ) Read and print numbers in base 2");
+    doc!("u.", "( u -- ) Print u as an unsigned number");
+    doc!(
+        ".r",
+        "( n width -- ) Print n right aligned in a field width characters wide"
+    );
+    doc!(
+        "u.r",
+        "( u width -- ) Print u unsigned, right aligned in a field width characters wide"
+    );
+    doc!(
+        "d.r",
+        "( d width -- ) Print the double d right aligned in a field width characters wide"
+    );
+    doc!("<#", "( -- ) Start pictured numeric output");
+    doc!("#", "( ud1 -- ud2 ) Add the lowest digit of ud1 to the pictured output, leaving ud1 divided by the base");
+    doc!(
+        "#s",
+        "( ud -- 0 0 ) Add all the digits of ud to the pictured output"
+    );
+    doc!(
+        "hold",
+        "( char -- ) Add char to the start of the pictured output"
+    );
+    doc!(
+        "holds",
+        "( c-addr u -- ) Add the string to the start of the pictured output"
+    );
+    doc!(
+        "sign",
+        "( n -- ) Add a minus sign to the start of the pictured output if n is negative"
+    );
+    doc!(
+        "#>",
+        "( xd -- c-addr u ) Finish pictured output, leaving it as a string"
+    );
+    doc!(
+        ".s\"",
+        "( c-addr u -- ) Print the string to stdout, in quotes"
+    );
+    doc!("type", "( c-addr u -- ) Print the string to stdout");
+    doc!(
+        "count",
+        "( c-addr1 -- c-addr2 u ) The string held in the counted string at c-addr1"
+    );
+    doc!(
+        "compare",
+        "( c-addr1 u1 c-addr2 u2 -- n ) -1, 0 or 1 as string 1 sorts before, the same as, or after string 2"
+    );
+    doc!(
+        "search",
+        "( c-addr1 u1 c-addr2 u2 -- c-addr3 u3 b ) Look for string 2 in string 1. If it is found,
+        push the rest of string 1 from the match, and true; otherwise push string 1 and false"
+    );
+    doc!(
+        "/string",
+        "( c-addr1 u1 n -- c-addr2 u2 ) Drop the first n characters of the string"
+    );
+    doc!(
+        "-trailing",
+        "( c-addr u1 -- c-addr u2 ) Drop the spaces at the end of the string"
+    );
+    doc!(
+        "cmove",
+        "( c-addr1 c-addr2 u -- ) Copy u characters from c-addr1 to c-addr2, lowest address first"
+    );
     doc!(
         "show-stack",
         "( -- )Enable automatic printing of the stack to the console after each line of input"
@@ -72,7 +144,7 @@ pub fn build_doc_strings() -> HashMap<String, String> {
         "( m n -- m n m ) Push a copy of the second item on the stack on top"
     );
     doc!(
-        "rotate",
+        "rot",
         "( i j k -- j k i ) Move the third stack item to the top"
     );
     doc!(
@@ -94,6 +166,17 @@ pub fn build_doc_strings() -> HashMap<String, String> {
          taking its value from the stack. 
          Use of the name places the value (not the address) on the stack. "
     );
+    doc!(
+        "create",
+        "usage: create <name> - creates a new word called <name>
+         Subsequent use of <name> places the address of the data space that followed it
+         on the stack. Use allot or , to reserve the space."
+    );
+    doc!(
+        "does>",
+        "Used in a definition after create. The code after does> becomes the behaviour of
+         the created word, which runs it with its data address on the stack."
+    );
     doc!(
         "@",
         "( addr -- value ) Replaces the address of a variable with its value"
@@ -102,19 +185,135 @@ pub fn build_doc_strings() -> HashMap<String, String> {
         "!",
         "( value address -- ) Stores value in the variable at address"
     );
+    doc!("c@", "( addr -- c ) Fetch the byte stored at addr");
+    doc!("c!", "( c addr -- ) Store the low byte of c at addr");
+    doc!(
+        "2@",
+        "( addr -- x1 x2 ) Fetch the pair of cells at addr; x2 is the one at addr"
+    );
+    doc!(
+        "2!",
+        "( x1 x2 addr -- ) Store a pair of cells at addr; x2 goes at addr"
+    );
+    doc!(
+        "here",
+        "( -- addr ) Push the address of the next free byte of the data space"
+    );
+    doc!(
+        "allot",
+        "( n -- ) Reserve n bytes of the data space, or release them if n is negative"
+    );
+    doc!(
+        ",",
+        "( x -- ) Reserve one cell of the data space and store x in it"
+    );
+    doc!(
+        "c,",
+        "( c -- ) Reserve one byte of the data space and store c in it"
+    );
+    doc!("align", "( -- ) Round here up to a cell boundary");
+    doc!(
+        "aligned",
+        "( addr -- addr' ) Round addr up to a cell boundary"
+    );
+    doc!("cells", "( n -- n*8 ) Convert a number of cells to bytes");
+    doc!("cell+", "( addr -- addr' ) Add the size of a cell to addr");
+    doc!(
+        "chars",
+        "( n -- n ) Convert a number of characters to bytes"
+    );
+    doc!(
+        "char+",
+        "( addr -- addr' ) Add the size of a character to addr"
+    );
+    doc!("fill", "( addr u c -- ) Set u bytes starting at addr to c");
+    doc!(
+        "erase",
+        "( addr u -- ) Set u bytes starting at addr to zero"
+    );
+    doc!(
+        "move",
+        "( from to u -- ) Copy u bytes from one address to another. The areas may overlap."
+    );
     doc!(
         "\\",
         "Defines an in-line comment. All text from the \\ to the end of line 
         will be ignored."
     );
+    doc!(
+        ">r",
+        "( n -- ) ( R: -- n ) Move the top of the stack to the return stack"
+    );
+    doc!(
+        "r>",
+        "( -- n ) ( R: n -- ) Move the top of the return stack to the stack"
+    );
+    doc!(
+        "r@",
+        "( -- n ) ( R: n -- n ) Copy the top of the return stack to the stack"
+    );
+    doc!(
+        "rdrop",
+        "( -- ) ( R: n -- ) Discard the top of the return stack"
+    );
+    doc!(
+        "2>r",
+        "( m n -- ) ( R: -- m n ) Move the top two stack items to the return stack"
+    );
+    doc!(
+        "2r>",
+        "( -- m n ) ( R: m n -- ) Move the top two return stack items to the stack"
+    );
+    doc!(
+        "2r@",
+        "( -- m n ) ( R: m n -- m n ) Copy the top two return stack items to the stack"
+    );
+    doc!(
+        "exit",
+        "( -- ) Return from the current definition, ending any DO loops it is running"
+    );
+    doc!(
+        "?exit",
+        "( b -- ) Return from the current definition if b is true"
+    );
+    doc!(
+        "unloop",
+        "( -- ) Discard the innermost DO loop's index and limit, before exit"
+    );
+    doc!("i", "( -- n ) Push the index of the innermost DO loop");
+    doc!("j", "( -- n ) Push the index of the next DO loop out");
     doc!("abort", "Ends the execution of the current word");
+    doc!(
+        "abort\"",
+        "Usage: abort\" <text>\" ( b -- ) If b is true, abort with the message <text> (throw code -2)"
+    );
+    doc!(
+        "throw",
+        "( n -- ) If n isn't 0, unwind to the innermost catch, or report error n and abort"
+    );
+    doc!(
+        "catch",
+        "( xt -- 0 | n ) Run xt. Push 0 if it finished, or the code it threw, with the stack depth restored"
+    );
     doc!(
         "words",
-        "Prints a list of the currently defined words. Does not list builtins"
+        "( -- ) Lists the currently defined words by kind, newest first, with counts"
+    );
+    doc!(
+        "words-like",
+        "Usage: words-like <filter> ( -- ) Lists the words whose names contain filter, as words does"
+    );
+    doc!(
+        "traverse-wordlist",
+        "( i*x xt wid -- j*x ) Run xt ( k*x nt -- l*x flag ) on each word in wid, newest first, until it leaves false"
+    );
+    doc!(
+        "name>string",
+        "( nt -- c-addr u ) The name of the word with name token nt"
     );
     doc!(
         "seeall",
-        "Prints the definitions of currently defined words. Does not list builtins."
+        "Prints the definitions of currently defined words, newest first. Does not list builtins."
     );
     doc!(
         "see",
@@ -125,13 +324,267 @@ pub fn build_doc_strings() -> HashMap<String, String> {
         "stack-depth",
         "( -- n ) Places a number representing the depth of the stack onto the stack"
     );
+    doc!(
+        "dbg",
+        "( n -- ) Set the debug level: 0 errors, 1 warnings, 2 info, 3 debug"
+    );
+    doc!("debuglevel?", "( -- ) Print the current debug level");
+    doc!("r/w", "( -- ) Set the file mode to read/write");
+    doc!("r/o", "( -- ) Set the file mode to read only");
+    doc!(
+        "loaded",
+        "( c-addr u -- ) Load the file named by the string"
+    );
+    doc!("bye", "( -- ) Leave the interpreter");
     doc!("step-on", "( -- ) Invokes the single-stepper");
     doc!("step-off", "( -- ) Disables the single-stepper");
+    doc!(
+        "wrap-on",
+        "( -- ) Integer overflow wraps around instead of being an error"
+    );
+    doc!(
+        "wrap-off",
+        "( -- ) Integer overflow is an error (the default)"
+    );
     doc!(
         ":",
         "Enter compile mode. Subsequent words up to a ';' will be added to the definition"
     );
     doc!(";", "Exits compile mode and saves the definition");
+    doc!(
+        "immediate",
+        "( -- ) Make the most recent definition immediate: it runs when it is used inside a definition, instead of being compiled"
+    );
+    doc!(
+        "postpone",
+        "Usage: postpone <word> Inside a definition, compile the word into the definition being compiled when this one runs.
+         An immediate word is run at that point instead."
+    );
+    doc!(
+        "[",
+        "( -- ) Inside a definition, run the following words straight away, until ]"
+    );
+    doc!("]", "( -- ) Go back to compiling the current definition");
+    doc!(
+        "literal",
+        "( x -- ) While compiling, add x to the definition, so it is pushed when the definition runs"
+    );
+    doc!(
+        "state",
+        "( -- addr ) Push the address of a cell holding true while compiling, false otherwise"
+    );
+    doc!(
+        "char",
+        "Usage: char <word> ( -- c ) Push the character code of the first character of the word"
+    );
+    doc!(
+        "[char]",
+        "Usage: [char] <word> ( -- c ) Inside a definition, push the character code of the first character of the word"
+    );
+    doc!(
+        "{:",
+        "Usage: {: a b | c -- d :} Inside a definition, declare locals. a and b take values from the stack,
+         c starts at zero, and everything after -- is a comment. A local pushes its value; to and +to change it."
+    );
+    doc!(
+        "locals|",
+        "Usage: locals| a b | Inside a definition, declare locals taking values from the stack. a gets the top item."
+    );
+    doc!(
+        "'",
+        "Usage: ' <word> ( -- xt ) Push the execution token of the word"
+    );
+    doc!(
+        "[']",
+        "Usage: ['] <word> ( -- xt ) Inside a definition, push the execution token of the word"
+    );
+    doc!(
+        "execute",
+        "( xt -- ) Run the word whose execution token is on the stack"
+    );
+    doc!(
+        "value",
+        "Usage: value <name> ( x -- ) Create a word that pushes x, which can be changed with to"
+    );
+    doc!(
+        "2value",
+        "Usage: 2value <name> ( x1 x2 -- ) Create a word that pushes x1 x2, which can be changed with to"
+    );
+    doc!(
+        "to",
+        "Usage: to <name> ( x -- ) or ( x1 x2 -- ) Change the contents of a value or 2value"
+    );
+    doc!("+to", "Usage: +to <name> ( n -- ) Add n to a value");
+    doc!("s>d", "( n -- d ) Convert n to a double");
+    doc!("m*", "( n1 n2 -- d ) Multiply, giving a double product");
+    doc!(
+        "um*",
+        "( u1 u2 -- ud ) Unsigned multiply, giving a double product"
+    );
+    doc!(
+        "um/mod",
+        "( ud u1 -- u2 u3 ) Divide ud by u1: remainder u2, quotient u3, all unsigned"
+    );
+    doc!(
+        "sm/rem",
+        "( d1 n1 -- n2 n3 ) Divide d1 by n1: remainder n2, quotient n3 rounded towards zero"
+    );
+    doc!(
+        "fm/mod",
+        "( d1 n1 -- n2 n3 ) Divide d1 by n1: remainder n2, quotient n3 rounded down"
+    );
+    doc!(
+        "*/",
+        "( n1 n2 n3 -- n4 ) n1*n2/n3 using a double intermediate product"
+    );
+    doc!("*/mod", "( n1 n2 n3 -- n4 n5 ) n1*n2/n3 using a double intermediate product: remainder n4, quotient n5");
+    doc!("d+", "( d1 d2 -- d1+d2 ) Add two doubles");
+    doc!(
+        "d-",
+        "( d1 d2 -- d1-d2 ) Subtract the top double from the second"
+    );
+    doc!("dnegate", "( d -- -d ) Negate a double");
+    doc!("d.", "( d -- ) Pop a double and print it");
+    doc!("f+", "( F: r1 r2 -- r1+r2 ) Add the top two floats");
+    doc!(
+        "f-",
+        "( F: r1 r2 -- r1-r2 ) Subtract the top float from the second"
+    );
+    doc!("f*", "( F: r1 r2 -- r1*r2 ) Multiply the top two floats");
+    doc!(
+        "f/",
+        "( F: r1 r2 -- r1/r2 ) Divide the second float by the top float"
+    );
+    doc!("f**", "( F: r1 r2 -- r1^r2 ) Raise r1 to the power r2");
+    doc!("fsqrt", "( F: r -- sqrt(r) ) Square root");
+    doc!("fsin", "( F: r -- sin(r) ) Sine of r radians");
+    doc!("fcos", "( F: r -- cos(r) ) Cosine of r radians");
+    doc!("fexp", "( F: r -- e^r ) Raise e to the power r");
+    doc!("fln", "( F: r -- ln(r) ) Natural logarithm");
+    doc!(
+        "f.",
+        "( F: r -- ) Pop the top of the float stack and print it"
+    );
+    doc!("fdup", "( F: r -- r r ) Duplicate the top float");
+    doc!("fdrop", "( F: r -- ) Discard the top float");
+    doc!("fswap", "( F: r1 r2 -- r2 r1 ) Swap the top two floats");
+    doc!(
+        "fover",
+        "( F: r1 r2 -- r1 r2 r1 ) Copy the second float to the top"
+    );
+    doc!(
+        "f<",
+        "( -- b ) ( F: r1 r2 -- ) If r1 < r2 push true else false"
+    );
+    doc!("f0=", "( -- b ) ( F: r -- ) If r == 0 push true else false");
+    doc!(
+        "s>f",
+        "( n -- ) ( F: -- r ) Move a number to the float stack"
+    );
+    doc!(
+        "f>s",
+        "( -- n ) ( F: r -- ) Move a float to the stack, dropping any fraction"
+    );
+    doc!(
+        "f@",
+        "( addr -- ) ( F: -- r ) Fetch the float stored at addr"
+    );
+    doc!("f!", "( addr -- ) ( F: r -- ) Store r at addr");
+    doc!(
+        "fvariable",
+        "Usage: fvariable <name> ( -- ) Create a variable to hold a float, used with f@ and f!"
+    );
+    doc!(
+        "fconstant",
+        "Usage: fconstant <name> ( F: r -- ) Create a word that pushes r on the float stack"
+    );
+    doc!("wordlist", "( -- wid ) Make a new, empty wordlist");
+    doc!(
+        "forth-wordlist",
+        "( -- wid ) Push the wordlist holding the builtins and the core library"
+    );
+    doc!(
+        "get-order",
+        "( -- widn .. wid1 n ) Push the search order. wid1 is searched first"
+    );
+    doc!(
+        "set-order",
+        "( widn .. wid1 n -- ) Set the search order. n of -1 sets the minimum search order"
+    );
+    doc!(
+        "get-current",
+        "( -- wid ) Push the wordlist new definitions go into"
+    );
+    doc!(
+        "set-current",
+        "( wid -- ) Put new definitions into the wordlist wid"
+    );
+    doc!(
+        "definitions",
+        "( -- ) Put new definitions into the first wordlist in the search order"
+    );
+    doc!(
+        "also",
+        "( -- ) Duplicate the first wordlist in the search order"
+    );
+    doc!(
+        "previous",
+        "( -- ) Remove the first wordlist from the search order"
+    );
+    doc!(
+        "only",
+        "( -- ) Set the minimum search order: the forth wordlist only"
+    );
+    doc!(
+        "forth",
+        "( -- ) Replace the first wordlist in the search order with the forth wordlist"
+    );
+    doc!(
+        "vocabulary",
+        "Usage: vocabulary <name> ( -- ) Create a word that replaces the first wordlist in the search order with a new wordlist"
+    );
+    doc!(
+        "order",
+        "( -- ) Print the search order and the wordlist new definitions go into"
+    );
+    doc!(
+        "marker",
+        "Usage: marker <name> ( -- ) Create a word that, when run, removes itself and every word defined after it,
+         and releases the data space they used. Wordlists made after it are removed too"
+    );
+    doc!(
+        "forget",
+        "Usage: forget <name> ( -- ) Remove the latest definition of the word, and every word defined after it"
+    );
+    doc!(
+        "defer",
+        "Usage: defer <name> ( -- ) Create a word that runs whatever word is later set with is"
+    );
+    doc!(
+        "is",
+        "Usage: is <name> ( xt -- ) Make the deferred word run the word with execution token xt"
+    );
+    doc!(
+        "action-of",
+        "Usage: action-of <name> ( -- xt ) Push the execution token a deferred word runs"
+    );
+    doc!(
+        "defer!",
+        "( xt2 xt1 -- ) Make the deferred word xt1 run the word xt2"
+    );
+    doc!(
+        "defer@",
+        "( xt1 -- xt2 ) Push the execution token the deferred word xt1 runs"
+    );
+    doc!(
+        ">body",
+        "( xt -- addr ) Push the data space address of a word made with create"
+    );
+    doc!(
+        "recurse",
+        "Inside a definition, calls the word being defined. 
+         Using the word's own name instead calls its previous definition, if there is one."
+    );
 
     doc_strings
 }
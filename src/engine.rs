@@ -4,48 +4,127 @@ use std::collections::HashMap;
 use std::io::{self, Write};
 
 use crate::doc;
-use crate::messages::{DebugLevel, Msg};
+use crate::messages::{throw_message, DebugLevel, Msg};
 use crate::reader::Reader;
-use crate::tokenizer::{BranchInfo, ForthToken, Tokenizer};
+use crate::tokenizer::{is_forward, unescape, BranchInfo, ForthToken, LocalInfo, Tokenizer};
+
+const MAX_CALL_DEPTH: usize = 10000; // nesting limit for definitions calling definitions
+const CELL_SIZE: i64 = 8; // bytes in a cell of the data space
+const MAX_DATA_SPACE: i64 = 16 * 1024 * 1024; // bytes available for variables and allot
+const STATE_ADDRESS: i64 = 0; // the first cell of the data space holds STATE
+const BASE_ADDRESS: i64 = CELL_SIZE; // the second holds BASE
+const STRING_BUFFER_ADDRESS: i64 = BASE_ADDRESS + CELL_SIZE; // transient buffers for strings made outside definitions follow BASE
+const STRING_BUFFERS: i64 = 4;
+const STRING_BUFFER_SIZE: i64 = 256;
+const HOLD_BUFFER_SIZE: i64 = 256; // pictured numeric output follows the string buffers, built back from the end
+const HOLD_BUFFER_END: i64 =
+    STRING_BUFFER_ADDRESS + STRING_BUFFERS * STRING_BUFFER_SIZE + HOLD_BUFFER_SIZE;
+const DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ"; // digits for bases up to 36
+const FORTH_WORDLIST: usize = 0; // builtins, the core library, and user words unless definitions changes it
+const ABORT: i64 = -1; // standard throw codes
+const ABORT_QUOTE: i64 = -2;
+const STACK_UNDERFLOW: i64 = -4;
+const RETURN_STACK_OVERFLOW: i64 = -5;
+const RETURN_STACK_UNDERFLOW: i64 = -6;
+const DICTIONARY_OVERFLOW: i64 = -8;
+const INVALID_MEMORY_ADDRESS: i64 = -9;
+const DIVISION_BY_ZERO: i64 = -10;
+const RESULT_OUT_OF_RANGE: i64 = -11;
+const ARGUMENT_TYPE_MISMATCH: i64 = -12;
+const UNDEFINED_WORD: i64 = -13;
+const PICTURED_OUTPUT_OVERFLOW: i64 = -17;
+const PARSED_STRING_OVERFLOW: i64 = -18;
+const LOOP_PARAMETERS_UNAVAILABLE: i64 = -26;
+const NOT_CREATED: i64 = -31;
+const FLOAT_STACK_UNDERFLOW: i64 = -45;
 
 #[derive(Debug)]
 struct ControlFrame {
-    id: usize,
     incr: i64,
     end: i64,
 }
 
 impl ControlFrame {
-    fn new(id: usize, start: i64, end: i64) -> ControlFrame {
-        ControlFrame {
-            id,
-            incr: start,
-            end,
-        }
+    fn new(start: i64, end: i64) -> ControlFrame {
+        ControlFrame { incr: start, end }
     }
 }
 
+#[derive(Debug)]
+struct CatchFrame {
+    // what catch restores when an exception is thrown
+    depth: usize,
+    float_depth: usize,
+    return_depth: usize,
+    control_depth: usize,
+    locals_depth: usize,
+}
+
+#[derive(Debug, Clone)]
+enum WordKind {
+    Builtin,                              // implemented in execute_builtin
+    Colon(Vec<ForthToken>),               // defined in Forth with : and ;
+    Variable(i64),                        // data space address
+    Constant(i64),                        // value
+    FConstant(f64),                       // floating point value
+    Created(i64, Option<(usize, usize)>), // data space address, does> code (definition, start token)
+    Deferred(Option<usize>),              // the execution token it runs, once set by is
+    Value(i64),                           // value, changed with to
+    TwoValue(i64, i64),                   // pair of values, changed with to
+    Marker(Vec<usize>, usize, usize), // removes itself and every later word when run; search order, current wordlist and number of wordlists to restore
+    Vocabulary(usize),                // wordlist it puts first in the search order
+}
+
+#[derive(Debug, Clone)]
+struct WordDefinition {
+    name: String,
+    kind: WordKind,
+    immediate: bool, // runs while a definition is being compiled, instead of being compiled
+    data_start: i64, // the data space in use before the word was defined, restored by forget
+    wordlist: usize, // the wordlist it was defined in
+}
+
 #[derive(Debug)]
 pub struct ForthInterpreter {
-    pub stack: Vec<i64>, // the numeric stack, currently integers
-    pub defined_words: HashMap<String, Vec<ForthToken>>, // the dictionary: keys (words) and their definitions
-    pub variable_stack: Vec<i64>,                        // where variables are stored
-    pub defined_variables: HashMap<String, i64>,         // separate hashmap for variables
-    pub defined_constants: HashMap<String, i64>,         // separate hashmap for constants
-    control_stack: Vec<ControlFrame>,                    // for do loops etc.
-    builtin_doc: HashMap<String, String>,                // doc strings for built-in words
-    text: String,                                        // the current s".."" string
+    pub stack: Vec<i64>,       // the numeric stack, currently integers
+    pub float_stack: Vec<f64>, // the floating point stack
+    pub defined_words: Vec<HashMap<String, usize>>, // the wordlists: each maps words to the execution token of their current definition
+    search_order: Vec<usize>, // the wordlists searched for a word, first searched first
+    current_wordlist: usize,  // the wordlist new definitions go into
+    word_definitions: Vec<WordDefinition>, // every word defined, including replaced ones. The index is the execution token.
+    pub data_space: Vec<u8>, // byte-addressed memory for variables etc.; its length is HERE
+    last_created: Option<usize>, // the word does> will change
+    control_stack: Vec<ControlFrame>, // for do loops etc.
+    return_stack: Vec<i64>,  // for >r, r> etc.
+    return_base: usize,      // return stack depth on entry to the executing definition
+    control_base: usize,     // control stack depth on entry: loops below it belong to callers
+    locals: Vec<i64>,        // the locals of every executing definition
+    locals_base: usize,      // start of the executing definition's locals
+    call_depth: usize,       // number of definitions currently executing
+    current_definition: usize, // index of the definition currently executing
+    builtin_doc: HashMap<String, String>, // doc strings for built-in words
+    next_buffer: i64,        // the transient string buffer to use next
+    hold_start: i64,         // the start of the pictured numeric output built so far
     file_mode: FileMode,
-    compile_mode: bool, // true if compiling a word
-    abort_flag: bool,   // true if abort has been called
-    exit_flag: bool,    // set when the "bye" word is executed.
+    compile_mode: bool,            // true if compiling a word
+    abort_flag: bool,              // true if abort has been called
+    exit_flag: bool,               // set when the "bye" word is executed.
+    return_flag: bool,             // set when "exit" leaves the current definition
+    catch_frames: Vec<CatchFrame>, // one for each catch that is running
+    thrown: Option<i64>,           // the code of an exception on its way to a catch
+    abort_text: String,            // the message of the latest abort"
     pub msg: Msg,
     parser: Tokenizer,
     new_word_name: String,
     new_word_definition: Vec<ForthToken>,
+    new_word_locals: Vec<String>, // names of the locals of the definition being compiled
+    new_word_start: i64,          // HERE at the :, before the definition's strings were saved
+    new_word_self_calls: Vec<usize>, // tokens that call the definition being compiled
+    new_word_first_xt: usize,     // the xt the next word got at the :
     token: ForthToken,
     show_stack: bool, // show the stack at the completion of a line of interaction
     step_mode: bool,
+    wrap_arithmetic: bool, // integer overflow wraps around instead of being an error
 }
 
 #[derive(Debug)]
@@ -62,33 +141,226 @@ impl ForthInterpreter {
         let doc_strings = doc::build_doc_strings();
         if let Some(reader) = Reader::new(None, main_prompt, multiline_prompt, Msg::new()) {
             let parser = Tokenizer::new(reader);
-            ForthInterpreter {
+            let mut forth = ForthInterpreter {
                 stack: Vec::new(),
-                defined_words: HashMap::new(),
-                text: String::new(),
-                variable_stack: Vec::new(),
-                // constant_stack: Vec::new(),
-                defined_variables: HashMap::new(),
-                defined_constants: HashMap::new(),
+                float_stack: Vec::new(),
+                defined_words: vec![HashMap::new()],
+                search_order: vec![FORTH_WORDLIST],
+                current_wordlist: FORTH_WORDLIST,
+                word_definitions: Vec::new(),
+                next_buffer: 0,
+                hold_start: HOLD_BUFFER_END,
+                data_space: Vec::new(),
+                last_created: None,
                 control_stack: Vec::new(),
+                return_stack: Vec::new(),
+                return_base: 0,
+                control_base: 0,
+                locals: Vec::new(),
+                locals_base: 0,
+                call_depth: 0,
+                current_definition: 0,
                 builtin_doc: doc_strings,
                 file_mode: FileMode::Unset,
                 compile_mode: false,
                 abort_flag: false,
                 exit_flag: false,
+                return_flag: false,
+                catch_frames: Vec::new(),
+                thrown: None,
+                abort_text: String::new(),
                 msg: Msg::new(),
                 parser,
                 new_word_name: String::new(),
                 new_word_definition: Vec::new(),
+                new_word_locals: Vec::new(),
+                new_word_start: 0,
+                new_word_self_calls: Vec::new(),
+                new_word_first_xt: 0,
                 token: ForthToken::Empty,
                 show_stack: false,
                 step_mode: false,
+                wrap_arithmetic: false,
+            };
+            // builtins are the first words in the dictionary, so they have execution tokens too
+            let mut builtins: Vec<String> = forth.builtin_doc.keys().cloned().collect();
+            builtins.sort();
+            for name in builtins {
+                forth.define_word(&name, WordKind::Builtin);
+            }
+            for name in ["[", "literal"] {
+                let xt = forth.defined_words[FORTH_WORDLIST][name];
+                forth.word_definitions[xt].immediate = true;
             }
+            // reserve the cells for STATE and BASE, the transient string buffers and the pictured output buffer
+            forth.data_space.resize(HOLD_BUFFER_END as usize, 0);
+            forth.store_cell("base", BASE_ADDRESS, 10);
+            forth
         } else {
             panic!("unable to create reader");
         }
     }
 
+    fn define_word(&mut self, name: &str, kind: WordKind) -> usize {
+        // add a word to the dictionary, returning its execution token
+        if self.defined_words[self.current_wordlist].contains_key(name) {
+            // shown at every debug level, since earlier definitions keep the old word
+            self.msg.notice("define", "redefining", Some(name));
+        }
+        let xt = self.word_definitions.len();
+        let data_start = match kind {
+            WordKind::Variable(address) | WordKind::Created(address, _) => address,
+            WordKind::Colon(_) => self.new_word_start,
+            _ => self.here(),
+        };
+        self.word_definitions.push(WordDefinition {
+            name: name.to_owned(),
+            kind,
+            immediate: false,
+            data_start,
+            wordlist: self.current_wordlist,
+        });
+        self.defined_words[self.current_wordlist].insert(name.to_owned(), xt);
+        xt
+    }
+
+    fn valid_wordlist(&self, wid: i64) -> bool {
+        wid >= 0 && (wid as usize) < self.defined_words.len()
+    }
+
+    fn wordlist_name(&self, wordlist: usize) -> String {
+        // the vocabulary that names the wordlist, if there is one
+        if wordlist == FORTH_WORDLIST {
+            return "forth".to_owned();
+        }
+        self.word_definitions
+            .iter()
+            .rev()
+            .find(|word| matches!(word.kind, WordKind::Vocabulary(w) if w == wordlist))
+            .map_or(format!("wordlist-{wordlist}"), |word| word.name.clone())
+    }
+
+    fn find_word(&self, name: &str) -> Option<usize> {
+        // the execution token of the first definition of name in the search order
+        self.search_order
+            .iter()
+            .find_map(|wordlist| self.defined_words[*wordlist].get(name).copied())
+    }
+
+    fn forget_from(&mut self, xt: usize) {
+        // remove the word with execution token xt and every word defined after it,
+        // and release the data space they used. A marker also restores the search order,
+        // and removes the wordlists made after it.
+        if let WordKind::Marker(order, current, wordlists) = &self.word_definitions[xt].kind {
+            self.search_order = order.clone();
+            self.current_wordlist = *current;
+            self.defined_words.truncate(*wordlists);
+        }
+        self.data_space
+            .truncate(self.word_definitions[xt].data_start as usize);
+        self.word_definitions.truncate(xt);
+        for wordlist in self.defined_words.iter_mut() {
+            wordlist.clear();
+        }
+        for (index, word) in self.word_definitions.iter_mut().enumerate() {
+            if let WordKind::Deferred(Some(action)) = word.kind {
+                if action >= xt {
+                    word.kind = WordKind::Deferred(None);
+                }
+            }
+            self.defined_words[word.wordlist].insert(word.name.clone(), index);
+        }
+        if self.last_created.is_some_and(|created| created >= xt) {
+            self.last_created = None;
+        }
+    }
+
+    pub fn set_wrap_arithmetic(&mut self, v: bool) {
+        self.wrap_arithmetic = v;
+    }
+
+    fn throw(&mut self, word: &str, code: i64) {
+        // unwind to the innermost catch; if there isn't one, report the error and abort
+        self.abort_flag = true;
+        if !self.catch_frames.is_empty() {
+            self.thrown = Some(code);
+            return;
+        }
+        match code {
+            ABORT => self
+                .msg
+                .warning("ABORT", "Terminating execution", None::<bool>),
+            ABORT_QUOTE if !self.abort_text.is_empty() => println!("{}", self.abort_text),
+            _ => self.msg.error(
+                word,
+                throw_message(code).unwrap_or("Uncaught exception"),
+                Some(code),
+            ),
+        }
+        self.stack.clear();
+        self.float_stack.clear();
+        self.parser.clear();
+        if self.compile_mode || !self.new_word_name.is_empty() {
+            // an error while compiling, even between [ and ], abandons the definition
+            self.discard_definition();
+        }
+    }
+
+    fn catch(&mut self, xt: usize) {
+        // run xt, then push 0, or the code of an exception it threw
+        self.catch_frames.push(CatchFrame {
+            depth: self.stack.len(),
+            float_depth: self.float_stack.len(),
+            return_depth: self.return_stack.len(),
+            control_depth: self.control_stack.len(),
+            locals_depth: self.locals.len(),
+        });
+        self.execute_xt(xt);
+        let frame = self.catch_frames.pop().unwrap();
+        if self.abort_flag {
+            // put the stacks back to the depths they had, so the caller can carry on
+            self.abort_flag = false;
+            self.stack.resize(frame.depth, 0);
+            self.float_stack.resize(frame.float_depth, 0.0);
+            self.return_stack.truncate(frame.return_depth);
+            self.control_stack.truncate(frame.control_depth);
+            self.locals.truncate(frame.locals_depth);
+            self.stack.push(self.thrown.take().unwrap_or(ABORT));
+        } else {
+            self.stack.push(0);
+        }
+    }
+
+    fn arithmetic(&mut self, word: &str, a: i64, b: i64) -> Option<i64> {
+        // a op b, or None after throwing division by zero or overflow.
+        // +to and +loop add, like +
+        if b == 0 && (word == "/" || word == "mod") {
+            self.throw(word, DIVISION_BY_ZERO);
+            return None;
+        }
+        let result = if self.wrap_arithmetic {
+            Some(match word {
+                "+" | "+to" | "+loop" => a.wrapping_add(b),
+                "-" => a.wrapping_sub(b),
+                "*" => a.wrapping_mul(b),
+                "/" => a.wrapping_div(b),
+                _ => a.wrapping_rem(b),
+            })
+        } else {
+            match word {
+                "+" | "+to" | "+loop" => a.checked_add(b),
+                "-" => a.checked_sub(b),
+                "*" => a.checked_mul(b),
+                "/" => a.checked_div(b),
+                _ => a.checked_rem(b),
+            }
+        };
+        if result.is_none() {
+            self.throw(word, RESULT_OUT_OF_RANGE);
+        }
+        result
+    }
+
     pub fn set_abort_flag(&mut self, v: bool) {
         self.abort_flag = v;
     }
@@ -109,11 +381,41 @@ impl ForthInterpreter {
 
     fn set_compile_mode(&mut self, state: bool) {
         self.compile_mode = state;
+        self.store_cell("state", STATE_ADDRESS, if state { -1 } else { 0 });
+    }
+
+    fn number_base(&self) -> u32 {
+        // the base for reading and printing numbers. If BASE has been set to something
+        // that can't be used, decimal is used instead
+        match self.cell_at(BASE_ADDRESS as usize) {
+            base if (2..=36).contains(&base) => base as u32,
+            _ => 10,
+        }
     }
 
-    fn stack_underflow(&self, op: &str, n: usize) -> bool {
+    fn number_text(&self, n: i128) -> String {
+        // n written in the current base
+        let digits = unsigned_text(n.unsigned_abs(), self.number_base());
+        if n < 0 {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+
+    fn stack_text(&self) -> String {
+        // the stack, written in the current base
+        let items: Vec<String> = self
+            .stack
+            .iter()
+            .map(|n| self.number_text(*n as i128))
+            .collect();
+        format!("[{}]", items.join(", "))
+    }
+
+    fn stack_underflow(&mut self, op: &str, n: usize) -> bool {
         if self.stack.len() < n {
-            self.msg.error(op, "Stack underflow", None::<bool>);
+            self.throw(op, STACK_UNDERFLOW);
             true
         } else {
             false
@@ -125,28 +427,124 @@ impl ForthInterpreter {
         match val {
             Some(value) => Some(value),
             None => {
-                self.msg.error(word, "Stack underflow", None::<bool>);
+                self.throw(word, STACK_UNDERFLOW);
                 None
             }
         }
     }
 
     fn pop_two(&mut self, word: &str) -> Option<(i64, i64)> {
-        let (val1, val2) = (self.stack.pop(), self.stack.pop());
-        match val1 {
-            Some(value1) => match val2 {
-                Some(value2) => Some((value1, value2)),
-                None => None,
-            },
-            None => {
-                self.msg.error(word, "Stack underflow", None::<bool>);
-                None
-            }
+        // returns (top, second). Nothing is popped if there aren't two values
+        if self.stack_underflow(word, 2) {
+            None
+        } else {
+            Some((self.stack.pop()?, self.stack.pop()?))
+        }
+    }
+
+    fn pop_double(&mut self, word: &str) -> Option<i128> {
+        // a double is two cells, with the high cell on top
+        let (high, low) = self.pop_two(word)?;
+        Some(((high as i128) << 64) | low as u64 as i128)
+    }
+
+    fn push_double(&mut self, d: i128) {
+        self.stack.push(d as i64);
+        self.stack.push((d >> 64) as i64);
+    }
+
+    fn divide_double(&mut self, word: &str, dividend: i128, divisor: i128, floored: bool) -> bool {
+        // push the remainder and the quotient, returning false if they can't be calculated
+        if divisor == 0 {
+            self.throw(word, DIVISION_BY_ZERO);
+            return false;
+        }
+        // the only other failure is the most negative double divided by -1
+        let (Some(mut quotient), Some(mut remainder)) =
+            (dividend.checked_div(divisor), dividend.checked_rem(divisor))
+        else {
+            self.throw(word, RESULT_OUT_OF_RANGE);
+            return false;
+        };
+        if floored && remainder != 0 && (remainder < 0) != (divisor < 0) {
+            quotient -= 1;
+            remainder += divisor;
+        }
+        if (quotient < i64::MIN as i128 || quotient > i64::MAX as i128) && !self.wrap_arithmetic {
+            self.throw(word, RESULT_OUT_OF_RANGE);
+            return false;
+        }
+        self.stack.push(remainder as i64);
+        self.stack.push(quotient as i64);
+        true
+    }
+
+    fn pop_float(&mut self, word: &str) -> Option<f64> {
+        let value = self.float_stack.pop();
+        if value.is_none() {
+            self.throw(word, FLOAT_STACK_UNDERFLOW);
+        }
+        value
+    }
+
+    fn pop_two_floats(&mut self, word: &str) -> Option<(f64, f64)> {
+        // returns (top, second)
+        if self.float_stack.len() < 2 {
+            self.throw(word, FLOAT_STACK_UNDERFLOW);
+            None
+        } else {
+            let top = self.float_stack.pop()?;
+            Some((top, self.float_stack.pop()?))
+        }
+    }
+
+    fn pop_three(&mut self, word: &str) -> Option<(i64, i64, i64)> {
+        // returns (top, second, third)
+        if self.stack_underflow(word, 3) {
+            None
+        } else if let (Some(a), Some(b), Some(c)) =
+            (self.stack.pop(), self.stack.pop(), self.stack.pop())
+        {
+            Some((a, b, c))
+        } else {
+            None
+        }
+    }
+
+    fn loop_frame(&mut self, word: &str) -> Option<usize> {
+        // the index of the innermost loop frame; loops below control_base belong to the caller
+        if self.control_stack.len() > self.control_base {
+            Some(self.control_stack.len() - 1)
+        } else {
+            self.throw(word, LOOP_PARAMETERS_UNAVAILABLE);
+            None
+        }
+    }
+
+    fn pop_return(&mut self, word: &str) -> Option<i64> {
+        // values below return_base belong to the calling definition
+        if self.return_stack.len() > self.return_base {
+            self.return_stack.pop()
+        } else {
+            self.throw(word, RETURN_STACK_UNDERFLOW);
+            None
         }
     }
 
     pub fn process_token(&mut self) -> bool {
-        let new_token = self.parser.get_token(&self.get_stack()); // Prompt if necessary, return a token
+        self.parser.set_base(self.number_base());
+        let stack = self.get_stack();
+        // the name after : and defined words and locals are words, whatever digits they contain
+        let naming = self.compile_mode && self.new_word_name.is_empty();
+        let (search_order, defined_words) = (&self.search_order, &self.defined_words);
+        let locals = &self.new_word_locals;
+        let new_token = self.parser.get_token(&stack, |name| {
+            naming
+                || locals.iter().any(|local| local == name)
+                || search_order
+                    .iter()
+                    .any(|wordlist| defined_words[*wordlist].contains_key(name))
+        }); // Prompt if necessary, return a token
         match new_token {
             Some(new_token) => {
                 self.msg
@@ -162,7 +560,12 @@ impl ForthInterpreter {
                             self.compile_token();
                         } else {
                             // we're in immediate mode
-                            self.execute_token(0, false);
+                            self.execute_token(0);
+                        }
+                        if self.catch_frames.is_empty() {
+                            // the error has been reported and there is no catch to unwind to,
+                            // so any abort is finished, even in a file loaded by a definition
+                            self.abort_flag = false;
                         }
                     }
                 }
@@ -181,18 +584,60 @@ impl ForthInterpreter {
             ForthToken::Operator(tstring) => {
                 if tstring == ";" {
                     // we are at the end of the definition
-                    self.calculate_branches();
-                    self.defined_words
-                        .insert(self.new_word_name.clone(), self.new_word_definition.clone());
-                    self.new_word_name.clear();
-                    self.new_word_definition.clear();
-                    self.set_compile_mode(false);
+                    if self.calculate_branches() {
+                        let name = self.new_word_name.clone();
+                        // the word gets the next xt now, whatever was defined while it was compiled
+                        let xt = self.word_definitions.len();
+                        for &idx in &self.new_word_self_calls {
+                            self.new_word_definition[idx] = ForthToken::Call(xt);
+                        }
+                        self.define_word(&name, WordKind::Colon(self.new_word_definition.clone()));
+                        self.end_definition();
+                    } else {
+                        self.discard_definition();
+                    }
                 } else if self.new_word_name.is_empty() {
                     // We've found the word name
                     self.new_word_name = tstring.to_string();
                 } else if tstring == ":" {
                     self.msg
                         .warning("compile_token", "Illegal inside definition", Some(":"));
+                } else if tstring == "{:" || tstring == "locals|" {
+                    let word = tstring.clone();
+                    self.compile_locals(&word);
+                } else if let Some(slot) = self.new_word_locals.iter().position(|n| n == tstring) {
+                    // locals hide any other word with the same name
+                    self.new_word_definition
+                        .push(ForthToken::Local(LocalInfo::new(
+                            "".to_owned(),
+                            tstring.clone(),
+                            slot,
+                        )));
+                } else if let Some(xt) = self.immediate_word(tstring) {
+                    // immediate words run now, while the definition is being compiled
+                    self.execute_xt(xt);
+                } else if tstring == "recurse" {
+                    // call the definition being compiled. Its xt isn't known until the ;
+                    self.new_word_self_calls
+                        .push(self.new_word_definition.len());
+                    self.new_word_definition
+                        .push(ForthToken::Call(self.word_definitions.len()));
+                } else if *tstring == self.new_word_name {
+                    // the word's own name refers to its previous definition until the ; is reached.
+                    // A new word can't see a previous definition, so it calls itself instead.
+                    let index = match self.find_word(tstring) {
+                        Some(previous) => previous,
+                        None => {
+                            self.new_word_self_calls
+                                .push(self.new_word_definition.len());
+                            self.word_definitions.len()
+                        }
+                    };
+                    self.new_word_definition.push(ForthToken::Call(index));
+                } else if let Some(xt) = self.find_word(tstring) {
+                    // bind the word now, so redefining it later only affects new definitions,
+                    // and it runs even when its wordlist is no longer in the search order
+                    self.new_word_definition.push(ForthToken::Call(xt));
                 } else {
                     // push the new token onto the definition
                     self.msg
@@ -200,6 +645,34 @@ impl ForthInterpreter {
                     self.new_word_definition.push(self.token.clone());
                 }
             }
+            ForthToken::Forward(info)
+                if (info.word == "to" || info.word == "+to")
+                    && self.new_word_locals.contains(&info.tail.trim().to_owned()) =>
+            {
+                let name = info.tail.trim().to_owned();
+                let slot = self.new_word_locals.iter().position(|n| *n == name);
+                self.new_word_definition
+                    .push(ForthToken::Local(LocalInfo::new(
+                        info.word.clone(),
+                        name,
+                        slot.unwrap_or_default(),
+                    )));
+            }
+            ForthToken::Forward(info)
+                if info.word == "s\"" || info.word == "c\"" || info.word == "s\\\"" =>
+            {
+                // the string is saved in the data space now, so it lasts as long as the definition
+                let (word, text) = (info.word.clone(), string_text(&info.tail).to_owned());
+                let bytes = string_bytes(&word, &info.tail);
+                if let Some(address) = self.store_string(&word, &bytes, word == "c\"", None) {
+                    self.new_word_definition.push(ForthToken::Text(
+                        word,
+                        text,
+                        address,
+                        bytes.len() as i64,
+                    ));
+                }
+            }
             _ => {
                 // Text, integer, float, comment all go into the new word definition
                 self.new_word_definition.push(self.token.clone());
@@ -207,85 +680,235 @@ impl ForthInterpreter {
         }
     }
 
-    fn calculate_branches(&mut self) {
+    fn discard_definition(&mut self) {
+        // abandon the definition being compiled after an error
+        self.msg.error(
+            "compile_token",
+            "Definition discarded",
+            Some(&self.new_word_name),
+        );
+        if self.word_definitions.len() == self.new_word_first_xt {
+            // release the strings it saved, unless a word defined between [ and ] owns data after them
+            self.data_space.truncate(self.new_word_start as usize);
+        }
+        self.end_definition();
+    }
+
+    fn end_definition(&mut self) {
+        // leave compile mode, ready for the next definition
+        self.new_word_name.clear();
+        self.new_word_definition.clear();
+        self.new_word_locals.clear();
+        self.new_word_self_calls.clear();
+        self.set_compile_mode(false);
+    }
+
+    fn compile_locals(&mut self, word: &str) {
+        // read a locals declaration, {: a b | c -- d :} or locals| b a |,
+        // and compile the token that sets up the frame when the definition runs.
+        // Names before | take values from the stack; names after it start at zero.
+        // Anything after -- is a comment. locals| names take values from the top of the stack down.
+        let end = if word == "{:" { ":}" } else { "|" };
+        let mut names = Vec::<String>::new();
+        let mut initialized = None;
+        let mut comment = false;
+        loop {
+            match self.parser.get_word() {
+                None => {
+                    self.msg
+                        .error(word, "Locals declaration not finished", Some(end));
+                    self.discard_definition();
+                    return;
+                }
+                Some(name) if name == end => break,
+                Some(_) if comment => {}
+                Some(name) if word == "{:" && name == "|" && initialized.is_none() => {
+                    initialized = Some(names.len());
+                }
+                Some(name) if word == "{:" && name == "--" => comment = true,
+                Some(name) => names.push(name),
+            }
+        }
+        if !self.new_word_locals.is_empty() {
+            self.msg.error(
+                word,
+                "Only one locals declaration is allowed in a definition",
+                Some(&self.new_word_name),
+            );
+            return;
+        }
+        if word == "locals|" {
+            names.reverse();
+        }
+        let initialized = initialized.unwrap_or(names.len());
+        self.new_word_locals = names.clone();
+        self.new_word_definition
+            .push(ForthToken::Locals(names, initialized));
+    }
+
+    fn branch_error(&self, word: &str, idx: usize, problem: &str) -> bool {
+        // report a badly formed control structure in the definition being compiled
+        self.msg.error(
+            &self.new_word_name,
+            &format!("{problem} at position {idx}"),
+            Some(word),
+        );
+        false
+    }
+
+    fn calculate_branches(&mut self) -> bool {
         // replace words that incorporate branches with ForthToken::Branch
         // and set up offsets as required.
-        let mut loop_stack = Vec::<(&str, usize, usize)>::new();
-        let mut conditional_stack = Vec::<(&str, usize, usize)>::new();
+        // Forward branches (if, else, while, of, endof, leave) jump to the token after their target;
+        // backward branches (until, again, repeat, loop) jump to the token after their start.
+        // Returns false, after reporting the problem, if a control structure is unbalanced.
+        let mut branch_stack = Vec::<(&str, usize, usize)>::new(); // open control structures
+        let mut leave_stack = Vec::<Vec<usize>>::new(); // the LEAVEs inside each open DO
         let mut idx = 0; // points to the current token
         while idx < self.new_word_definition.len() {
             let cur_token = self.new_word_definition[idx].clone();
             if let ForthToken::Branch(branch_info) = cur_token {
-                let word = &branch_info.word;
+                let word = branch_info.word.as_str();
+                let upper = word.to_uppercase();
 
-                match word.as_str() {
+                match word {
                     "if" => {
                         // put the info on the stack
-                        conditional_stack.push(("if", idx, branch_info.branch_id));
+                        branch_stack.push(("if", idx, branch_info.branch_id));
                     }
                     "else" => {
-                        // pop stack, insert new ZeroEqual Branch token with offset
-                        if let Some((_word, place, id)) = conditional_stack.pop() {
-                            self.new_word_definition[place] = ForthToken::Branch(BranchInfo::new(
-                                "if".to_string(),
-                                idx - place,
-                                id,
-                            ));
-                            conditional_stack.push(("else", idx, id));
+                        // resolve the IF (or WHILE) to jump past the ELSE, and leave the ELSE to be resolved by THEN
+                        match branch_stack.pop() {
+                            Some((orig @ ("if" | "while"), place, id)) => {
+                                self.new_word_definition[place] = ForthToken::Branch(
+                                    BranchInfo::new(orig.to_owned(), idx - place, id),
+                                );
+                                branch_stack.push(("else", idx, id));
+                            }
+                            _ => return self.branch_error(word, idx, "ELSE without IF"),
+                        }
+                    }
+                    "then" | "endif" => {
+                        // pop stack, set the offset of the IF, ELSE or WHILE being resolved
+                        match branch_stack.pop() {
+                            Some((orig @ ("if" | "else" | "while"), place, id)) => {
+                                self.new_word_definition[place] = ForthToken::Branch(
+                                    BranchInfo::new(orig.to_owned(), idx - place, id),
+                                );
+                                self.new_word_definition[idx] =
+                                    ForthToken::Branch(BranchInfo::new("then".to_string(), 0, id));
+                            }
+                            _ => {
+                                return self.branch_error(word, idx, &format!("{upper} without IF"))
+                            }
+                        }
+                    }
+                    "begin" => {
+                        branch_stack.push(("begin", idx, branch_info.branch_id));
+                    }
+                    "while" => {
+                        // the WHILE goes underneath its BEGIN, so REPEAT finds the BEGIN first
+                        match branch_stack.pop() {
+                            Some(begin @ ("begin", _, _)) => {
+                                branch_stack.push(("while", idx, branch_info.branch_id));
+                                branch_stack.push(begin);
+                            }
+                            _ => return self.branch_error(word, idx, "WHILE without BEGIN"),
                         }
                     }
-                    "then" => {
-                        // pop stack, insert new Unconditional Branch token with offset
-                        if let Some((word, place, id)) = conditional_stack.pop() {
-                            self.new_word_definition[place] = ForthToken::Branch(BranchInfo::new(
+                    "until" | "again" => match branch_stack.pop() {
+                        Some(("begin", place, id)) => {
+                            self.new_word_definition[idx] = ForthToken::Branch(BranchInfo::new(
                                 word.to_owned(),
                                 idx - place,
-                                id, // IF statements don't need a branch_id
+                                id,
                             ));
-                            self.new_word_definition[idx] =
-                                ForthToken::Branch(BranchInfo::new("then".to_string(), 0, id));
+                        }
+                        _ => {
+                            return self.branch_error(word, idx, &format!("{upper} without BEGIN"))
+                        }
+                    },
+                    "repeat" => {
+                        // branch back to the BEGIN, then resolve the most recent WHILE to exit here.
+                        // Any further WHILEs are left for a following THEN or ELSE.
+                        match branch_stack.pop() {
+                            Some(("begin", place, id)) => {
+                                self.new_word_definition[idx] = ForthToken::Branch(
+                                    BranchInfo::new("repeat".to_owned(), idx - place, id),
+                                );
+                            }
+                            _ => return self.branch_error(word, idx, "REPEAT without BEGIN"),
+                        }
+                        match branch_stack.pop() {
+                            Some(("while", place, id)) => {
+                                self.new_word_definition[place] = ForthToken::Branch(
+                                    BranchInfo::new("while".to_owned(), idx - place, id),
+                                );
+                            }
+                            _ => return self.branch_error(word, idx, "REPEAT without WHILE"),
                         }
                     }
-                    "do" => {
-                        // push onto branch_stack
-                        loop_stack.push(("do", idx, branch_info.branch_id));
+                    "case" => {
+                        branch_stack.push(("case", idx, branch_info.branch_id));
                     }
-                    "leave" => {
-                        loop_stack.push(("leave", idx, branch_info.branch_id));
+                    "of" => {
+                        branch_stack.push(("of", idx, branch_info.branch_id));
                     }
-                    "loop" | "+loop" => {
-                        let mut branch_id = 0;
-                        // Get one record for a start
-                        if let Some((name, place, id)) = loop_stack.pop() {
-                            let loop_id = id; // capture id for consistency
-                            let mut delta: usize = 0; // capture branch-back distance from do
-                            if name == "leave" {
+                    "endof" => {
+                        // the OF skips to here when it doesn't match; ENDCASE resolves the ENDOF
+                        match branch_stack.pop() {
+                            Some(("of", place, id)) => {
                                 self.new_word_definition[place] = ForthToken::Branch(
-                                    BranchInfo::new("leave".to_owned(), idx - place, loop_id),
+                                    BranchInfo::new("of".to_owned(), idx - place, id),
                                 );
-                                branch_id = id; // save for the DO and LOOP records
-                            } else {
-                                // it must be a DO
-                                self.new_word_definition[place] = ForthToken::Branch(
-                                    BranchInfo::new("do".to_owned(), idx - place - 1, loop_id),
-                                );
-                                delta = place;
+                                branch_stack.push(("endof", idx, id));
                             }
-                            // if it was LEAVE, we need another record; otherwise proceed to (+)LOOP
-                            if branch_id != 0 {
-                                // get another record
-                                if let Some((_name, place, _id)) = loop_stack.pop() {
+                            _ => return self.branch_error(word, idx, "ENDOF without OF"),
+                        }
+                    }
+                    "endcase" => {
+                        // every ENDOF in this CASE jumps past the ENDCASE
+                        loop {
+                            match branch_stack.pop() {
+                                Some(("endof", place, id)) => {
                                     self.new_word_definition[place] = ForthToken::Branch(
-                                        BranchInfo::new("do".to_owned(), idx - place - 1, loop_id),
+                                        BranchInfo::new("endof".to_owned(), idx - place, id),
                                     );
                                 }
+                                Some(("case", _place, _id)) => break,
+                                _ => return self.branch_error(word, idx, "ENDCASE without CASE"),
+                            }
+                        }
+                    }
+                    "do" => {
+                        // push onto branch_stack
+                        branch_stack.push(("do", idx, branch_info.branch_id));
+                        leave_stack.push(Vec::new());
+                    }
+                    "leave" => match leave_stack.last_mut() {
+                        Some(leaves) => leaves.push(idx),
+                        None => return self.branch_error(word, idx, "LEAVE outside a DO loop"),
+                    },
+                    "loop" | "+loop" => {
+                        match branch_stack.pop() {
+                            Some(("do", place, id)) => {
+                                self.new_word_definition[place] = ForthToken::Branch(
+                                    BranchInfo::new("do".to_owned(), idx - place, id),
+                                );
+                                // LEAVEs exit past the LOOP
+                                for leave in leave_stack.pop().unwrap_or_default() {
+                                    self.new_word_definition[leave] = ForthToken::Branch(
+                                        BranchInfo::new("leave".to_owned(), idx - leave, id),
+                                    );
+                                }
+                                // process the LOOP token
+                                self.new_word_definition[idx] = ForthToken::Branch(
+                                    BranchInfo::new(word.to_owned(), idx - place, id),
+                                );
+                            }
+                            _ => {
+                                return self.branch_error(word, idx, &format!("{upper} without DO"))
                             }
-                            // process the LOOP token
-                            self.new_word_definition[idx] = ForthToken::Branch(BranchInfo::new(
-                                word.clone(),
-                                idx - delta + 1,
-                                loop_id,
-                            ));
                         }
                     }
                     _ => {}
@@ -293,36 +916,113 @@ impl ForthInterpreter {
             }
             idx += 1;
         }
+        // anything still open was never closed
+        if let Some((word, place, _id)) = branch_stack.pop() {
+            let problem = match word {
+                "if" => "IF without THEN",
+                "else" => "ELSE without THEN",
+                "begin" => "BEGIN without UNTIL, AGAIN or REPEAT",
+                "while" => "WHILE without REPEAT",
+                "do" => "DO without LOOP",
+                "of" => "OF without ENDOF",
+                _ => "CASE without ENDCASE",
+            };
+            return self.branch_error(word, place, problem);
+        }
+        true
     }
 
-    fn execute_token(&mut self, mut program_counter: usize, mut jumped: bool) -> (usize, bool) {
+    fn execute_token(&mut self, mut program_counter: usize) -> usize {
         // Execute a defined token
         self.step(); // gets a debug char if enabled
         program_counter += 1; // base assumption is we're processing one word
-        match &self.token {
-            ForthToken::Empty => return (program_counter, false),
+        let token = self.token.clone(); // leaves self free to be mutated while executing
+        match &token {
+            ForthToken::Empty => return program_counter,
             ForthToken::Integer(num) => {
                 self.stack.push(*num);
             }
-            ForthToken::Float(_num) => {
-                // TBD: a separate stack is used for floating point calculations
+            ForthToken::Text(word, _, address, length) => {
+                // ( -- c-addr u ) for s" and s\", ( -- c-addr ) for c"
+                self.stack.push(*address);
+                if word != "c\"" {
+                    self.stack.push(*length);
+                }
+            }
+            ForthToken::Float(num) => {
+                // floating point numbers have a stack of their own
+                self.float_stack.push(*num);
+            }
+            ForthToken::Call(xt) => {
+                // a word bound when it was compiled. Builtins get the program counter, which does> needs
+                if matches!(self.word_definitions[*xt].kind, WordKind::Builtin) {
+                    let name = self.word_definitions[*xt].name.clone();
+                    self.execute_word(&name, None, program_counter);
+                } else {
+                    self.execute_xt(*xt);
+                }
+            }
+            ForthToken::Locals(names, initialized) => {
+                // start this definition's frame, taking the first values from the stack
+                let depth = self.stack.len();
+                if depth < *initialized {
+                    self.throw("{:", STACK_UNDERFLOW);
+                } else {
+                    let values = self.stack.split_off(depth - *initialized);
+                    self.locals.truncate(self.locals_base);
+                    self.locals.extend(values);
+                    self.locals.resize(self.locals_base + names.len(), 0);
+                }
+            }
+            ForthToken::Local(info) => {
+                let slot = self.locals_base + info.slot;
+                if slot >= self.locals.len() {
+                    self.msg
+                        .error(&info.name, "Local has not been set up", None::<bool>);
+                } else if info.word.is_empty() {
+                    self.stack.push(self.locals[slot]);
+                } else if let Some(n) = self.pop_one(&info.word) {
+                    if info.word == "+to" {
+                        if let Some(x) = self.arithmetic("+to", self.locals[slot], n) {
+                            self.locals[slot] = x;
+                        }
+                    } else {
+                        self.locals[slot] = n;
+                    }
+                }
             }
             ForthToken::Forward(info) => {
                 match info.word.as_str() {
                     "(" => {} // ignore comments
                     ".\"" => {
-                        let tail = &info.tail[1..info.tail.len() - 1];
-                        println!("{}", tail);
+                        println!("{}", string_text(&info.tail));
                     }
-                    "s\"" => {
-                        let txt = &info.tail;
-                        self.text = info.tail[1..txt.len() - 1].to_owned();
+                    ".\\\"" => {
+                        let text = unescape(string_text(&info.tail));
+                        println!("{}", String::from_utf8_lossy(&text));
+                    }
+                    "s\"" | "c\"" | "s\\\"" => {
+                        // ( -- c-addr u ) or ( -- c-addr ) the string goes in a transient buffer
+                        let counted = info.word == "c\"";
+                        let buffer = STRING_BUFFER_ADDRESS + self.next_buffer * STRING_BUFFER_SIZE;
+                        let bytes = string_bytes(&info.word, &info.tail);
+                        if let Some(address) =
+                            self.store_string(&info.word, &bytes, counted, Some(buffer))
+                        {
+                            self.next_buffer = (self.next_buffer + 1) % STRING_BUFFERS;
+                            self.stack.push(address);
+                            if !counted {
+                                self.stack.push(bytes.len() as i64);
+                            }
+                        }
                     }
                     "variable" => {
-                        let index = self.variable_stack.len();
-                        self.variable_stack.push(0); // create the location for the new variable
-                        self.defined_variables
-                            .insert(info.tail.trim().to_owned(), index as i64);
+                        // create the location for the new variable
+                        self.align();
+                        let address = self.here();
+                        if self.allot("variable", CELL_SIZE) {
+                            self.define_word(info.tail.trim(), WordKind::Variable(address));
+                        }
                         self.msg.debug(
                             "execute_token",
                             "Dealing with a variable called",
@@ -332,19 +1032,27 @@ impl ForthInterpreter {
                     "constant" => {
                         // Create the element and store its value from the stack
                         if let Some(constant_value) = self.stack.pop() {
-                            self.defined_constants
-                                .insert(info.tail.trim().to_owned(), constant_value);
+                            self.define_word(info.tail.trim(), WordKind::Constant(constant_value));
                             self.msg.debug(
                                 "execute_token",
                                 "Dealing with a constant called",
                                 Some(&info.tail),
                             );
                         } else {
-                            self.msg.error(
-                                "constant",
-                                "Stack underflow.",
-                                Some("Constant needs value"),
-                            );
+                            self.throw("constant", STACK_UNDERFLOW);
+                        }
+                    }
+                    "fvariable" => {
+                        // create the location for the new floating point variable
+                        self.align();
+                        let address = self.here();
+                        if self.allot("fvariable", CELL_SIZE) {
+                            self.define_word(info.tail.trim(), WordKind::Variable(address));
+                        }
+                    }
+                    "fconstant" => {
+                        if let Some(value) = self.pop_float("fconstant") {
+                            self.define_word(info.tail.trim(), WordKind::FConstant(value));
                         }
                     }
                     "see" => {
@@ -352,6 +1060,68 @@ impl ForthInterpreter {
                         // a builtin's documentation string
                         self.word_see(info.tail.trim());
                     }
+                    "[']" => {
+                        // ( -- xt ) the name was read when the definition was compiled
+                        self.tick("[']", info.tail.trim());
+                    }
+                    "[char]" => {
+                        // ( -- c ) the character was read when the definition was compiled
+                        self.push_char("[char]", info.tail.trim());
+                    }
+                    "postpone" => {
+                        // run an immediate word now; compile any other word into the definition being compiled
+                        let name = info.tail.trim();
+                        if !self.compile_mode {
+                            self.msg.error(
+                                "postpone",
+                                "Can only be used while compiling",
+                                Some(name),
+                            );
+                        } else if let Some(xt) = self.immediate_word(name) {
+                            self.execute_xt(xt);
+                        } else if is_forward(name) || name.is_empty() {
+                            self.msg.error("postpone", "Can't postpone", Some(name));
+                        } else {
+                            let defined = self.find_word(name).is_some();
+                            self.token = self.parser.word_token(name.to_owned(), defined);
+                            self.compile_token();
+                        }
+                    }
+                    "is" => {
+                        // ( xt "name" -- ) set the word a deferred word runs
+                        if let Some(deferred) = self.find("is", info.tail.trim()) {
+                            if let Some(action) = self.pop_one("is") {
+                                self.defer_store("is", deferred as i64, action);
+                            }
+                        }
+                    }
+                    "to" => {
+                        // ( x "name" -- ) or ( x1 x2 "name" -- ) change a value
+                        if let Some(xt) = self.find("to", info.tail.trim()) {
+                            self.value_store("to", xt, false);
+                        }
+                    }
+                    "+to" => {
+                        // ( n "name" -- ) add n to a value
+                        if let Some(xt) = self.find("+to", info.tail.trim()) {
+                            self.value_store("+to", xt, true);
+                        }
+                    }
+                    "action-of" => {
+                        // ( "name" -- xt ) the word a deferred word runs
+                        if let Some(deferred) = self.find("action-of", info.tail.trim()) {
+                            self.defer_fetch("action-of", deferred as i64);
+                        }
+                    }
+                    "abort\"" => {
+                        // ( b -- ) abort with a message if b is true
+                        if let Some(b) = self.pop_one("abort\"") {
+                            if b != 0 {
+                                self.abort_text = string_text(&info.tail).to_owned();
+                                self.throw("abort\"", ABORT_QUOTE);
+                            }
+                        }
+                    }
                     "\\" => {
                         // comment: no execution action
                     }
@@ -361,89 +1131,96 @@ impl ForthInterpreter {
             ForthToken::Branch(info) => {
                 match info.word.as_str() {
                     // runtime semantics
-                    "if" => {
-                        if !self.stack_underflow("if", 1) {
-                            let b = self.stack.pop();
-                            if b.unwrap() == 0 {
+                    "if" | "while" => {
+                        // ( b -- ) skip forward when the flag is false
+                        let b = self.pop_one(&info.word);
+                        if b == Some(0) {
+                            program_counter += info.offset;
+                        }
+                    }
+                    "else" => {
+                        // reached the end of the true branch; skip the false branch
+                        program_counter += info.offset;
+                    }
+                    "then" | "begin" | "case" => {}
+                    "of" => {
+                        // ( x1 x2 -- | x1 ) if they match, drop both and run the clause,
+                        // otherwise keep x1 and skip to the next clause
+                        if let Some((x2, x1)) = self.pop_two("of") {
+                            if x1 != x2 {
+                                self.stack.push(x1);
                                 program_counter += info.offset;
-                                jumped = true;
-                            } else {
-                                jumped = false;
                             }
                         }
                     }
-                    "else" => {
-                        if jumped {
-                            jumped = false
-                        } else {
-                            program_counter += info.offset;
-                            jumped = true;
+                    "endof" => {
+                        // the clause is finished; skip to the end of the CASE
+                        program_counter += info.offset;
+                    }
+                    "endcase" => {
+                        // ( x -- ) no clause matched: discard the case selector
+                        self.pop_one("endcase");
+                    }
+                    "until" => {
+                        // ( b -- ) go back to the BEGIN until the flag is true
+                        let b = self.pop_one("until");
+                        if b == Some(0) {
+                            program_counter -= info.offset;
                         }
                     }
-                    "then" => {
-                        jumped = false;
+                    "again" | "repeat" => {
+                        program_counter -= info.offset;
                     }
                     "do" => {
                         // ( limit first -- )
-                        // first time, (branch_id is not top of control stack) grab limit and first values
-                        // and put them on the control stack
-                        if self.control_stack.is_empty()
-                            || self.control_stack[self.control_stack.len() - 1].id != info.branch_id
-                        {
-                            // it's our first time
-                            // place popped values on the control stack
-                            if let (Some(init), Some(end)) = (self.stack.pop(), self.stack.pop()) {
-                                self.control_stack.push(ControlFrame::new(
-                                    info.branch_id,
-                                    init,
-                                    end,
-                                ));
-                            } else {
-                                self.msg.error(
-                                    "execute_token",
-                                    "DO requires END and INIT values on stack",
-                                    None::<bool>,
-                                );
-                                self.abort_flag = true;
-                                return (program_counter, jumped);
+                        // grab limit and first values and put them on the control stack.
+                        // LOOP branches back to the token after the DO, so this only runs on entry.
+                        match self.pop_two("do") {
+                            Some((init, end)) => {
+                                self.control_stack.push(ControlFrame::new(init, end))
                             }
+                            None => return program_counter,
                         }
                     }
                     "loop" => {
-                        let current_frame = self.control_stack.len() - 1;
+                        let Some(current_frame) = self.loop_frame("loop") else {
+                            return program_counter;
+                        };
                         self.control_stack[current_frame].incr += 1;
                         if self.control_stack[current_frame].incr
                             < self.control_stack[current_frame].end
                         {
                             program_counter -= info.offset;
-                            jumped = true;
                         } else {
-                            jumped = false;
                             self.control_stack.pop();
                         }
                     }
                     "+loop" => {
                         // get the increment from the calculation stack
                         if let Some(increment) = self.stack.pop() {
-                            let current_frame = self.control_stack.len() - 1;
-                            self.control_stack[current_frame].incr += increment;
+                            let Some(current_frame) = self.loop_frame("+loop") else {
+                                return program_counter;
+                            };
+                            let index = self.control_stack[current_frame].incr;
+                            match self.arithmetic("+loop", index, increment) {
+                                Some(index) => self.control_stack[current_frame].incr = index,
+                                None => return program_counter,
+                            }
                             if self.control_stack[current_frame].incr
                                 < self.control_stack[current_frame].end
                             {
                                 program_counter -= info.offset;
-                                jumped = true;
                             } else {
-                                // program_counter += 1;
-                                jumped = false;
                                 self.control_stack.pop();
                             }
                         } else {
-                            self.msg
-                                .error("+loop", "No increment value on stack", None::<bool>);
-                            self.abort_flag = true;
+                            self.throw("+loop", STACK_UNDERFLOW);
                         }
                     }
                     "leave" => {
+                        if self.loop_frame("leave").is_none() {
+                            return program_counter;
+                        }
                         self.control_stack.pop();
                         program_counter += info.offset;
                     }
@@ -451,293 +1228,1335 @@ impl ForthInterpreter {
                 }
             }
             ForthToken::Operator(op) => {
-                macro_rules! pop2_push1 {
-                    // Helper macro
-                    ($word:expr, $expression:expr) => {
-                        if let Some((j, k)) = self.pop_two(&$word) {
-                            self.stack.push($expression(k, j));
-                        }
-                    };
+                let xt = self.find_word(op);
+                self.execute_word(op, xt, program_counter);
+            }
+        }
+        program_counter
+    }
+
+    fn execute_word(&mut self, name: &str, xt: Option<usize>, program_counter: usize) {
+        // the dictionary is searched first, so the latest definition of a name wins,
+        // even over a builtin with the same name
+        match xt {
+            Some(xt) if !matches!(self.word_definitions[xt].kind, WordKind::Builtin) => {
+                self.execute_xt(xt);
+            }
+            _ => {
+                if !self.execute_builtin(name, program_counter) {
+                    self.throw(name, UNDEFINED_WORD);
                 }
-                macro_rules! pop1_push1 {
-                    // Helper macro
-                    ($word:expr, $expression:expr) => {
-                        if let Some(x) = self.pop_one(&$word) {
-                            self.stack.push($expression(x));
-                        }
-                    };
+            }
+        }
+    }
+
+    fn execute_builtin(&mut self, op: &str, program_counter: usize) -> bool {
+        // run a builtin word, returning false if there is no builtin called op
+        macro_rules! pop2_push1 {
+            // Helper macro
+            ($word:expr, $expression:expr) => {
+                if let Some((j, k)) = self.pop_two(&$word) {
+                    self.stack.push($expression(k, j));
                 }
-                macro_rules! pop1 {
-                    ($word:expr, $code:expr) => {
-                        if let Some(x) = self.pop_one(&$word) {
-                            $code(x);
-                        }
-                    };
+            };
+        }
+        macro_rules! pop1_push1 {
+            // Helper macro
+            ($word:expr, $expression:expr) => {
+                if let Some(x) = self.pop_one(&$word) {
+                    self.stack.push($expression(x));
                 }
-                match op.as_str() {
-                    "+" => pop2_push1!("+", |a, b| a + b),
-                    "-" => pop2_push1!("-", |a, b| a - b),
-                    "*" => pop2_push1!("*", |a, b| a * b),
-                    "/" => pop2_push1!("/", |a, b| a / b),
-                    "mod" => pop2_push1!("mod", |a, b| a % b),
-                    "<" => pop2_push1!("<", |a, b| if a < b { -1 } else { 0 }),
-                    "." => pop1!(".", |a| print!("{a} ")),
-                    "true" => self.stack.push(-1),
-                    "false" => self.stack.push(0),
-                    "=" => pop2_push1!("=", |a, b| if a == b { -1 } else { 0 }),
-                    "0=" => pop1_push1!("0=", |a| if a == 0 { -1 } else { 0 }),
-                    "0<" => pop1_push1!("0<", |a| if a < 0 { -1 } else { 0 }),
-                    ".s" => {
-                        // print stack contents
-                        println!("{:?}", self.stack);
-                    }
-                    "cr" => println!(""),
-                    "show-stack" => {
-                        self.show_stack = true;
-                    }
-                    "hide-stack" => {
-                        self.show_stack = false;
-                    }
-                    ".s\"" => {
-                        // print the saved string
-                        print!("{:?}", self.text);
-                    }
-                    "emit" => {
-                        if !self.stack_underflow("echo", 1) {
-                            let n = self.stack.pop();
-                            if let Some(n) = n {
-                                if (0x20..=0x7f).contains(&n) {
-                                    let c = n as u8 as char;
-                                    print!("{}", c);
-                                } else {
-                                    self.msg.error("EMIT", "Arg out of range", Some(n));
-                                }
-                            }
-                        }
-                    }
-                    "flush" => {
-                        // flush the stdout buffer to the terminal
-                        io::stdout().flush().unwrap();
-                    }
-                    "clear" => {
-                        self.stack.clear();
-                    }
-                    "dup" => {
-                        if let Some(top) = self.stack.last() {
-                            self.stack.push(*top);
-                        } else {
-                            self.msg
-                                .warning("DUP", "Error - DUP: Stack is empty.", None::<bool>);
-                        }
-                    }
-                    "drop" => pop1!("drop", |_a| ()),
-                    "swap" => {
-                        if self.stack.len() > 1 {
-                            let a = self.stack[self.stack.len() - 1];
-                            let b = self.stack[self.stack.len() - 2];
-                            self.stack.pop();
-                            self.stack.pop();
-                            self.stack.push(a);
-                            self.stack.push(b);
-                        } else {
-                            self.msg
-                                .warning("SWAP", "Too few elements on stack.", None::<bool>);
+            };
+        }
+        macro_rules! pop1 {
+            ($word:expr, $code:expr) => {
+                if let Some(x) = self.pop_one(&$word) {
+                    $code(x);
+                }
+            };
+        }
+        macro_rules! fpop2_push1 {
+            // Helper macro for the float stack
+            ($word:expr, $expression:expr) => {
+                if let Some((j, k)) = self.pop_two_floats(&$word) {
+                    self.float_stack.push($expression(k, j));
+                }
+            };
+        }
+        macro_rules! fpop1_push1 {
+            // Helper macro for the float stack
+            ($word:expr, $expression:expr) => {
+                if let Some(x) = self.pop_float(&$word) {
+                    self.float_stack.push($expression(x));
+                }
+            };
+        }
+        match op {
+            "+" | "-" | "*" | "/" | "mod" => {
+                if let Some((b, a)) = self.pop_two(op) {
+                    if let Some(result) = self.arithmetic(op, a, b) {
+                        self.stack.push(result);
+                    }
+                }
+            }
+            "<" => pop2_push1!("<", |a, b| if a < b { -1 } else { 0 }),
+            "." => {
+                if let Some(n) = self.pop_one(".") {
+                    print!("{} ", self.number_text(n as i128));
+                }
+            }
+            "u." => {
+                if let Some(u) = self.pop_one("u.") {
+                    print!("{} ", unsigned_text(u as u64 as u128, self.number_base()));
+                }
+            }
+            ".r" | "u.r" => {
+                // ( n width -- ) print n right aligned in a field width characters wide
+                if let Some((width, n)) = self.pop_two(op) {
+                    let text = if op == ".r" {
+                        self.number_text(n as i128)
+                    } else {
+                        unsigned_text(n as u64 as u128, self.number_base())
+                    };
+                    print!("{text:>0$}", width.max(0) as usize);
+                }
+            }
+            "d.r" => {
+                // ( d width -- ) print d right aligned in a field width characters wide
+                if let Some(width) = self.pop_one("d.r") {
+                    if let Some(d) = self.pop_double("d.r") {
+                        print!("{:>1$}", self.number_text(d), width.max(0) as usize);
+                    }
+                }
+            }
+            "base" => self.stack.push(BASE_ADDRESS),
+            "decimal" => self.store_cell("decimal", BASE_ADDRESS, 10),
+            "hex" => self.store_cell("hex", BASE_ADDRESS, 16),
+            "octal" => self.store_cell("octal", BASE_ADDRESS, 8),
+            "binary" => self.store_cell("binary", BASE_ADDRESS, 2),
+            "<#" => self.hold_start = HOLD_BUFFER_END,
+            "hold" => {
+                // ( char -- ) add char to the start of the pictured output
+                if let Some(c) = self.pop_one("hold") {
+                    self.hold("hold", &[c as u8]);
+                }
+            }
+            "holds" => {
+                // ( c-addr u -- ) add the string to the start of the pictured output
+                if let Some(text) = self.pop_string("holds") {
+                    self.hold("holds", &text);
+                }
+            }
+            "sign" => {
+                // ( n -- ) add a minus sign to the start of the pictured output if n is negative
+                if let Some(n) = self.pop_one("sign") {
+                    if n < 0 {
+                        self.hold("sign", b"-");
+                    }
+                }
+            }
+            "#" | "#s" => {
+                // ( ud1 -- ud2 ) add the lowest digit of ud1 to the pictured output;
+                // #s adds all of them
+                if let Some(d) = self.pop_double(op) {
+                    let base = self.number_base() as u128;
+                    let mut ud = d as u128;
+                    loop {
+                        let digit = DIGITS[(ud % base) as usize];
+                        ud /= base;
+                        if !self.hold(op, &[digit]) || op == "#" || ud == 0 {
+                            break;
                         }
                     }
-                    "over" => {
-                        if self.stack_underflow("OVER", 2) {
-                            self.abort_flag = true;
+                    self.push_double(ud as i128);
+                }
+            }
+            "#>" => {
+                // ( xd -- c-addr u ) finish the pictured output, dropping the number
+                if self.pop_double("#>").is_some() {
+                    self.stack.push(self.hold_start);
+                    self.stack.push(HOLD_BUFFER_END - self.hold_start);
+                }
+            }
+            "true" => self.stack.push(-1),
+            "false" => self.stack.push(0),
+            "=" => pop2_push1!("=", |a, b| if a == b { -1 } else { 0 }),
+            "0=" => pop1_push1!("0=", |a| if a == 0 { -1 } else { 0 }),
+            "0<" => pop1_push1!("0<", |a| if a < 0 { -1 } else { 0 }),
+            ".s" => {
+                // print stack contents
+                println!("{}", self.stack_text());
+                if !self.float_stack.is_empty() {
+                    println!("F: {:?}", self.float_stack);
+                }
+            }
+            "s>d" => {
+                // ( n -- d ) extend n to a double
+                if let Some(n) = self.pop_one("s>d") {
+                    self.push_double(n as i128);
+                }
+            }
+            "m*" => {
+                // ( n1 n2 -- d ) multiply, giving a double product
+                if let Some((a, b)) = self.pop_two("m*") {
+                    self.push_double(a as i128 * b as i128);
+                }
+            }
+            "um*" => {
+                // ( u1 u2 -- ud ) unsigned multiply, giving a double product
+                if let Some((a, b)) = self.pop_two("um*") {
+                    self.push_double((a as u64 as u128 * b as u64 as u128) as i128);
+                }
+            }
+            "um/mod" => {
+                // ( ud u1 -- u2 u3 ) unsigned division of a double: remainder u2, quotient u3
+                if let Some(divisor) = self.pop_one("um/mod") {
+                    if let Some(dividend) = self.pop_double("um/mod") {
+                        let (dividend, divisor) = (dividend as u128, divisor as u64 as u128);
+                        if divisor == 0 {
+                            self.throw("um/mod", DIVISION_BY_ZERO);
+                        } else if dividend / divisor > u64::MAX as u128 && !self.wrap_arithmetic {
+                            self.throw("um/mod", RESULT_OUT_OF_RANGE);
                         } else {
-                            let item = self.stack.get(self.stack.len() - 2);
-                            match item {
-                                Some(item) => {
-                                    self.stack.push(*item);
-                                }
-                                None => {
-                                    self.abort_flag = true;
-                                }
-                            }
+                            self.stack.push((dividend % divisor) as u64 as i64);
+                            self.stack.push((dividend / divisor) as u64 as i64);
                         }
                     }
-                    "rot" => {
-                        if self.stack_underflow("OVER", 3) {
-                            self.abort_flag = true;
+                }
+            }
+            "sm/rem" | "fm/mod" => {
+                // ( d1 n1 -- n2 n3 ) divide a double: remainder n2, quotient n3.
+                // sm/rem rounds the quotient towards zero, fm/mod rounds it down
+                if let Some(divisor) = self.pop_one(op) {
+                    if let Some(dividend) = self.pop_double(op) {
+                        self.divide_double(op, dividend, divisor as i128, op == "fm/mod");
+                    }
+                }
+            }
+            "*/" | "*/mod" => {
+                // ( n1 n2 n3 -- n4 n5 ) n1*n2/n3 with a double intermediate product:
+                // remainder n4 (*/mod only), quotient n5
+                if let Some((n3, n2, n1)) = self.pop_three(op) {
+                    if self.divide_double(op, n1 as i128 * n2 as i128, n3 as i128, false)
+                        && op == "*/"
+                    {
+                        // keep only the quotient
+                        self.stack.swap_remove(self.stack.len() - 2);
+                    }
+                }
+            }
+            "d+" | "d-" => {
+                // ( d1 d2 -- d3 )
+                if let Some(d2) = self.pop_double(op) {
+                    if let Some(d1) = self.pop_double(op) {
+                        self.push_double(if op == "d+" {
+                            d1.wrapping_add(d2)
                         } else {
-                            let top_index = self.stack.len() - 1;
-                            let top = self.stack[top_index - 2];
-                            let middle = self.stack[top_index];
-                            let bottom = self.stack[top_index - 1];
-                            self.stack[top_index - 2] = bottom;
-                            self.stack[top_index - 1] = middle;
-                            self.stack[top_index] = top;
-                        }
-                    }
-                    "and" => {
-                        if !self.stack_underflow("AND", 2) {
-                            if let (Some(a), Some(b)) = (self.stack.pop(), self.stack.pop()) {
-                                self.stack.push(a & b);
-                            }
-                        }
+                            d1.wrapping_sub(d2)
+                        });
                     }
-                    "or" => {
-                        if !self.stack_underflow("OR", 2) {
-                            if let (Some(a), Some(b)) = (self.stack.pop(), self.stack.pop()) {
-                                self.stack.push(a | b);
-                            }
-                        }
+                }
+            }
+            "dnegate" => {
+                if let Some(d) = self.pop_double("dnegate") {
+                    self.push_double(d.wrapping_neg());
+                }
+            }
+            "d." => {
+                if let Some(d) = self.pop_double("d.") {
+                    print!("{} ", self.number_text(d));
+                }
+            }
+            "f+" => fpop2_push1!("f+", |a, b| a + b),
+            "f-" => fpop2_push1!("f-", |a, b| a - b),
+            "f*" => fpop2_push1!("f*", |a, b| a * b),
+            "f/" => fpop2_push1!("f/", |a, b| a / b),
+            "f**" => fpop2_push1!("f**", |a: f64, b| a.powf(b)),
+            "fsqrt" => fpop1_push1!("fsqrt", |a: f64| a.sqrt()),
+            "fsin" => fpop1_push1!("fsin", |a: f64| a.sin()),
+            "fcos" => fpop1_push1!("fcos", |a: f64| a.cos()),
+            "fexp" => fpop1_push1!("fexp", |a: f64| a.exp()),
+            "fln" => fpop1_push1!("fln", |a: f64| a.ln()),
+            "f." => {
+                if let Some(r) = self.pop_float("f.") {
+                    print!("{r:?} ");
+                }
+            }
+            "fdup" => {
+                if let Some(a) = self.pop_float("fdup") {
+                    self.float_stack.push(a);
+                    self.float_stack.push(a);
+                }
+            }
+            "fdrop" => {
+                self.pop_float("fdrop");
+            }
+            "fswap" => {
+                if let Some((a, b)) = self.pop_two_floats("fswap") {
+                    self.float_stack.push(a);
+                    self.float_stack.push(b);
+                }
+            }
+            "fover" => {
+                if let Some((a, b)) = self.pop_two_floats("fover") {
+                    self.float_stack.push(b);
+                    self.float_stack.push(a);
+                    self.float_stack.push(b);
+                }
+            }
+            "f<" => {
+                // ( -- b ) ( F: r1 r2 -- )
+                if let Some((a, b)) = self.pop_two_floats("f<") {
+                    self.stack.push(if b < a { -1 } else { 0 });
+                }
+            }
+            "f0=" => {
+                // ( -- b ) ( F: r -- )
+                if let Some(a) = self.pop_float("f0=") {
+                    self.stack.push(if a == 0.0 { -1 } else { 0 });
+                }
+            }
+            "s>f" => {
+                // ( n -- ) ( F: -- r )
+                if let Some(n) = self.pop_one("s>f") {
+                    self.float_stack.push(n as f64);
+                }
+            }
+            "f>s" => {
+                // ( -- n ) ( F: r -- ) the fraction is dropped
+                if let Some(r) = self.pop_float("f>s") {
+                    self.stack.push(r as i64);
+                }
+            }
+            "f@" => {
+                // ( addr -- ) ( F: -- r )
+                if let Some(address) = self.pop_one("f@") {
+                    if let Some(bits) = self.fetch_cell("f@", address) {
+                        self.float_stack.push(f64::from_bits(bits as u64));
                     }
-                    "@" => {
-                        if !self.stack_underflow("@", 1) {
-                            if let Some(adr) = self.stack.pop() {
-                                let address = adr.max(0) as usize;
-                                if address < self.variable_stack.len() {
-                                    self.stack.push(self.variable_stack[address]);
-                                } else {
-                                    self.msg.error("@", "Bad variable address", Some(adr));
-                                }
-                            }
-                        }
+                }
+            }
+            "f!" => {
+                // ( addr -- ) ( F: r -- )
+                if let Some(address) = self.pop_one("f!") {
+                    if let Some(r) = self.pop_float("f!") {
+                        self.store_cell("f!", address, r.to_bits() as i64);
                     }
-                    "!" => {
-                        if !self.stack_underflow("!", 2) {
-                            if let (Some(addr), Some(val)) = (self.stack.pop(), self.stack.pop()) {
-                                let address = addr.max(0) as usize;
-                                if address < self.variable_stack.len() {
-                                    self.variable_stack[address] = val;
+                }
+            }
+            "cr" => println!(),
+            "show-stack" => {
+                self.show_stack = true;
+            }
+            "hide-stack" => {
+                self.show_stack = false;
+            }
+            ".s\"" => {
+                // ( c-addr u -- ) print the string in quotes
+                if let Some(text) = self.pop_string(".s\"") {
+                    print!("{:?}", String::from_utf8_lossy(&text));
+                }
+            }
+            "type" => {
+                // ( c-addr u -- ) print the string
+                if let Some(text) = self.pop_string("type") {
+                    print!("{}", String::from_utf8_lossy(&text));
+                }
+            }
+            "count" => {
+                // ( c-addr1 -- c-addr2 u ) the string in a counted string
+                if let Some(addr) = self.pop_one("count") {
+                    if let Some(address) = self.address("count", addr, 1) {
+                        self.stack.push(addr + 1);
+                        self.stack.push(self.data_space[address] as i64);
+                    }
+                }
+            }
+            "compare" => {
+                // ( c-addr1 u1 c-addr2 u2 -- n ) -1, 0 or 1 as string 1 sorts before, with or after string 2
+                if let Some(text2) = self.pop_string("compare") {
+                    if let Some(text1) = self.pop_string("compare") {
+                        self.stack.push(match text1.cmp(&text2) {
+                            std::cmp::Ordering::Less => -1,
+                            std::cmp::Ordering::Equal => 0,
+                            std::cmp::Ordering::Greater => 1,
+                        });
+                    }
+                }
+            }
+            "search" => {
+                // ( c-addr1 u1 c-addr2 u2 -- c-addr3 u3 b ) look for string 2 in string 1.
+                // If it is found, c-addr3 u3 is the rest of string 1, starting with the match
+                if let Some(text2) = self.pop_string("search") {
+                    if let Some((u1, addr1)) = self.pop_two("search") {
+                        if let Some(text1) = self.string_at("search", addr1, u1) {
+                            let found = if text2.is_empty() {
+                                Some(0)
+                            } else {
+                                text1.windows(text2.len()).position(|w| w == text2)
+                            };
+                            match found {
+                                Some(offset) => {
+                                    self.stack.push(addr1 + offset as i64);
+                                    self.stack.push(u1 - offset as i64);
+                                    self.stack.push(-1);
+                                }
+                                None => {
+                                    self.stack.push(addr1);
+                                    self.stack.push(u1);
+                                    self.stack.push(0);
                                 }
                             }
                         }
                     }
-                    "i" => {
-                        // print the index of the current top-level loop
-                        if self.control_stack.is_empty() {
-                            self.msg.warning(
-                                "I",
-                                "Can only be used inside a DO .. LOOP structure",
-                                None::<bool>,
-                            );
-                        } else {
-                            self.stack
-                                .push(self.control_stack[self.control_stack.len() - 1].incr);
+                }
+            }
+            "/string" => {
+                // ( c-addr1 u1 n -- c-addr2 u2 ) drop the first n characters of the string
+                if let Some((n, u, addr)) = self.pop_three("/string") {
+                    self.stack.push(addr + n);
+                    self.stack.push(u - n);
+                }
+            }
+            "-trailing" => {
+                // ( c-addr u1 -- c-addr u2 ) drop the spaces at the end of the string
+                if let Some((u, addr)) = self.pop_two("-trailing") {
+                    if let Some(text) = self.string_at("-trailing", addr, u) {
+                        let trimmed = text.iter().rposition(|c| *c != b' ').map_or(0, |i| i + 1);
+                        self.stack.push(addr);
+                        self.stack.push(trimmed as i64);
+                    }
+                }
+            }
+            "cmove" => {
+                // ( c-addr1 c-addr2 u -- ) copy u characters from c-addr1 to c-addr2, lowest first
+                if let Some((u, to, from)) = self.pop_three("cmove") {
+                    if let (Some(from), Some(to)) =
+                        (self.address("cmove", from, u), self.address("cmove", to, u))
+                    {
+                        for i in 0..u.max(0) as usize {
+                            self.data_space[to + i] = self.data_space[from + i];
                         }
                     }
-                    "j" => {
-                        // print the index of the current second-level (outer) loop
-                        if self.control_stack.len() < 2 {
-                            self.msg.warning(
-                                "I",
-                                "Can only be used inside a nested DO .. LOOP structure",
-                                None::<bool>,
-                            );
+                }
+            }
+            "emit" => {
+                if !self.stack_underflow("echo", 1) {
+                    let n = self.stack.pop();
+                    if let Some(n) = n {
+                        if (0x20..=0x7f).contains(&n) {
+                            let c = n as u8 as char;
+                            print!("{}", c);
                         } else {
-                            self.stack
-                                .push(self.control_stack[self.control_stack.len() - 2].incr);
+                            self.msg.error("EMIT", "Arg out of range", Some(n));
                         }
                     }
-                    "abort" => {
-                        // empty the stack, reset any pending operations, and return to the prompt
-                        self.msg
-                            .warning("ABORT", "Terminating execution", None::<bool>);
-                        self.stack.clear();
-                        self.parser.clear();
-                        self.abort_flag = true;
+                }
+            }
+            "flush" => {
+                // flush the stdout buffer to the terminal
+                io::stdout().flush().unwrap();
+            }
+            "clear" => {
+                self.stack.clear();
+                self.float_stack.clear();
+            }
+            "dup" => {
+                if let Some(top) = self.stack.last() {
+                    self.stack.push(*top);
+                } else {
+                    self.throw("dup", STACK_UNDERFLOW);
+                }
+            }
+            "drop" => pop1!("drop", |_a| ()),
+            "swap" => {
+                if self.stack.len() > 1 {
+                    let a = self.stack[self.stack.len() - 1];
+                    let b = self.stack[self.stack.len() - 2];
+                    self.stack.pop();
+                    self.stack.pop();
+                    self.stack.push(a);
+                    self.stack.push(b);
+                } else {
+                    self.throw("swap", STACK_UNDERFLOW);
+                }
+            }
+            "over" => {
+                if !self.stack_underflow("over", 2) {
+                    self.stack.push(self.stack[self.stack.len() - 2]);
+                }
+            }
+            "rot" => {
+                if !self.stack_underflow("rot", 3) {
+                    let top_index = self.stack.len() - 1;
+                    let top = self.stack[top_index - 2];
+                    let middle = self.stack[top_index];
+                    let bottom = self.stack[top_index - 1];
+                    self.stack[top_index - 2] = bottom;
+                    self.stack[top_index - 1] = middle;
+                    self.stack[top_index] = top;
+                }
+            }
+            "and" => {
+                if !self.stack_underflow("AND", 2) {
+                    if let (Some(a), Some(b)) = (self.stack.pop(), self.stack.pop()) {
+                        self.stack.push(a & b);
+                    }
+                }
+            }
+            "or" => {
+                if !self.stack_underflow("OR", 2) {
+                    if let (Some(a), Some(b)) = (self.stack.pop(), self.stack.pop()) {
+                        self.stack.push(a | b);
+                    }
+                }
+            }
+            "@" => {
+                if let Some(addr) = self.pop_one("@") {
+                    if let Some(value) = self.fetch_cell("@", addr) {
+                        self.stack.push(value);
+                    }
+                }
+            }
+            "!" => {
+                if let Some((addr, val)) = self.pop_two("!") {
+                    self.store_cell("!", addr, val);
+                }
+            }
+            "c@" => {
+                if let Some(addr) = self.pop_one("c@") {
+                    if let Some(address) = self.address("c@", addr, 1) {
+                        self.stack.push(self.data_space[address] as i64);
+                    }
+                }
+            }
+            "c!" => {
+                if let Some((addr, val)) = self.pop_two("c!") {
+                    if let Some(address) = self.address("c!", addr, 1) {
+                        self.data_space[address] = val as u8;
+                    }
+                }
+            }
+            "2@" => {
+                // ( addr -- x1 x2 ) x2 is stored at addr, x1 in the next cell
+                if let Some(addr) = self.pop_one("2@") {
+                    if let Some(address) = self.address("2@", addr, 2 * CELL_SIZE) {
+                        self.stack.push(self.cell_at(address + CELL_SIZE as usize));
+                        self.stack.push(self.cell_at(address));
+                    }
+                }
+            }
+            "2!" => {
+                if let Some((addr, x2, x1)) = self.pop_three("2!") {
+                    if let Some(address) = self.address("2!", addr, 2 * CELL_SIZE) {
+                        self.set_cell_at(address, x2);
+                        self.set_cell_at(address + CELL_SIZE as usize, x1);
+                    }
+                }
+            }
+            "here" => self.stack.push(self.here()),
+            "allot" => {
+                if let Some(n) = self.pop_one("allot") {
+                    self.allot("allot", n);
+                }
+            }
+            "," => {
+                if let Some(val) = self.pop_one(",") {
+                    let address = self.here();
+                    if self.allot(",", CELL_SIZE) {
+                        self.store_cell(",", address, val);
+                    }
+                }
+            }
+            "c," => {
+                if let Some(val) = self.pop_one("c,") {
+                    if self.allot("c,", 1) {
+                        let address = self.data_space.len() - 1;
+                        self.data_space[address] = val as u8;
+                    }
+                }
+            }
+            "align" => self.align(),
+            "aligned" => {
+                if let Some(addr) = self.pop_one("aligned") {
+                    match aligned(addr) {
+                        Some(address) => self.stack.push(address),
+                        None => self.throw("aligned", INVALID_MEMORY_ADDRESS),
                     }
-                    "words" => {
-                        for (key, _) in self.defined_words.iter() {
-                            print!("{key} ");
+                }
+            }
+            "cells" => pop1_push1!("cells", |a: i64| a.wrapping_mul(CELL_SIZE)),
+            "cell+" => pop1_push1!("cell+", |a: i64| a.wrapping_add(CELL_SIZE)),
+            "chars" => pop1_push1!("chars", |a| a),
+            "char+" => pop1_push1!("char+", |a: i64| a.wrapping_add(1)),
+            "fill" => {
+                // ( addr u char -- )
+                if let Some((c, u, addr)) = self.pop_three("fill") {
+                    if let Some(address) = self.address("fill", addr, u) {
+                        self.data_space[address..address + u as usize].fill(c as u8);
+                    }
+                }
+            }
+            "erase" => {
+                // ( addr u -- )
+                if let Some((u, addr)) = self.pop_two("erase") {
+                    if let Some(address) = self.address("erase", addr, u) {
+                        self.data_space[address..address + u as usize].fill(0);
+                    }
+                }
+            }
+            "move" => {
+                // ( from to u -- ) copies u bytes; the regions may overlap
+                if let Some((u, to, from)) = self.pop_three("move") {
+                    if let (Some(source), Some(dest)) =
+                        (self.address("move", from, u), self.address("move", to, u))
+                    {
+                        self.data_space
+                            .copy_within(source..source + u as usize, dest);
+                    }
+                }
+            }
+            "i" => {
+                // print the index of the current top-level loop
+                if self.control_stack.is_empty() {
+                    self.msg.warning(
+                        "I",
+                        "Can only be used inside a DO .. LOOP structure",
+                        None::<bool>,
+                    );
+                } else {
+                    self.stack
+                        .push(self.control_stack[self.control_stack.len() - 1].incr);
+                }
+            }
+            "j" => {
+                // print the index of the current second-level (outer) loop
+                if self.control_stack.len() < 2 {
+                    self.msg.warning(
+                        "I",
+                        "Can only be used inside a nested DO .. LOOP structure",
+                        None::<bool>,
+                    );
+                } else {
+                    self.stack
+                        .push(self.control_stack[self.control_stack.len() - 2].incr);
+                }
+            }
+            ">r" => pop1!(">r", |a| self.return_stack.push(a)),
+            "r>" => {
+                if let Some(a) = self.pop_return("r>") {
+                    self.stack.push(a);
+                }
+            }
+            "r@" => {
+                if self.return_stack.len() > self.return_base {
+                    self.stack
+                        .push(self.return_stack[self.return_stack.len() - 1]);
+                } else {
+                    self.throw("r@", RETURN_STACK_UNDERFLOW);
+                }
+            }
+            "rdrop" => {
+                self.pop_return("rdrop");
+            }
+            "2>r" => {
+                if let Some((b, a)) = self.pop_two("2>r") {
+                    self.return_stack.push(a);
+                    self.return_stack.push(b);
+                }
+            }
+            "2r>" => {
+                if self.return_stack.len() < self.return_base + 2 {
+                    self.throw("2r>", RETURN_STACK_UNDERFLOW);
+                } else if let (Some(b), Some(a)) =
+                    (self.return_stack.pop(), self.return_stack.pop())
+                {
+                    self.stack.push(a);
+                    self.stack.push(b);
+                }
+            }
+            "2r@" => {
+                let depth = self.return_stack.len();
+                if depth < self.return_base + 2 {
+                    self.throw("2r@", RETURN_STACK_UNDERFLOW);
+                } else {
+                    self.stack.push(self.return_stack[depth - 2]);
+                    self.stack.push(self.return_stack[depth - 1]);
+                }
+            }
+            "exit" => self.exit_definition("exit"),
+            "?exit" => {
+                if let Some(b) = self.pop_one("?exit") {
+                    if b != 0 {
+                        self.exit_definition("?exit");
+                    }
+                }
+            }
+            "unloop" => {
+                if self.loop_frame("unloop").is_some() {
+                    self.control_stack.pop();
+                }
+            }
+            "create" => {
+                // ( "name" -- ) the new word pushes the address of the data space that follows it
+                match self.parser.get_word() {
+                    Some(name) => {
+                        self.align();
+                        let xt = self.define_word(&name, WordKind::Created(self.here(), None));
+                        self.last_created = Some(xt);
+                    }
+                    None => self.msg.error("create", "Name required", None::<bool>),
+                }
+            }
+            "defer" => {
+                // ( "name" -- ) the new word runs whatever execution token is set with is
+                match self.parser.get_word() {
+                    Some(name) => {
+                        self.define_word(&name, WordKind::Deferred(None));
+                    }
+                    None => self.msg.error("defer", "Name required", None::<bool>),
+                }
+            }
+            "value" => {
+                // ( x "name" -- ) the new word pushes x until it is changed with to
+                match self.parser.get_word() {
+                    Some(name) => {
+                        if let Some(x) = self.pop_one("value") {
+                            self.define_word(&name, WordKind::Value(x));
                         }
-                        println!();
                     }
-                    "seeall" => {
-                        for (key, _definition) in self.defined_words.iter() {
-                            self.word_see(key);
+                    None => self.msg.error("value", "Name required", None::<bool>),
+                }
+            }
+            "2value" => {
+                // ( x1 x2 "name" -- ) the new word pushes x1 x2 until they are changed with to
+                match self.parser.get_word() {
+                    Some(name) => {
+                        if let Some((x2, x1)) = self.pop_two("2value") {
+                            self.define_word(&name, WordKind::TwoValue(x1, x2));
                         }
-                        for (key, index) in self.defined_variables.iter() {
-                            self.variable_see(key, *index);
+                    }
+                    None => self.msg.error("2value", "Name required", None::<bool>),
+                }
+            }
+            "marker" => {
+                // ( "name" -- ) the new word restores the dictionary and data space to this point
+                match self.parser.get_word() {
+                    Some(name) => {
+                        let order = self.search_order.clone();
+                        let wordlists = self.defined_words.len();
+                        self.define_word(
+                            &name,
+                            WordKind::Marker(order, self.current_wordlist, wordlists),
+                        );
+                    }
+                    None => self.msg.error("marker", "Name required", None::<bool>),
+                }
+            }
+            "forget" => {
+                // ( "name" -- ) remove the latest definition of name, and every word defined after it
+                match self.parser.get_word() {
+                    Some(name) => {
+                        if let Some(xt) = self.find("forget", &name) {
+                            if matches!(self.word_definitions[xt].kind, WordKind::Builtin) {
+                                self.msg
+                                    .error("forget", "Builtins can't be forgotten", Some(name));
+                            } else {
+                                self.forget_from(xt);
+                            }
                         }
                     }
-                    "stack-depth" => {
-                        self.stack.push(self.stack.len() as i64);
+                    None => self.msg.error("forget", "Name required", None::<bool>),
+                }
+            }
+            "wordlist" => {
+                // ( -- wid ) make a new, empty wordlist
+                self.defined_words.push(HashMap::new());
+                self.stack.push(self.defined_words.len() as i64 - 1);
+            }
+            "forth-wordlist" => self.stack.push(FORTH_WORDLIST as i64),
+            "get-order" => {
+                // ( -- widn .. wid1 n ) wid1 is searched first
+                for wordlist in self.search_order.iter().rev() {
+                    self.stack.push(*wordlist as i64);
+                }
+                self.stack.push(self.search_order.len() as i64);
+            }
+            "set-order" => {
+                // ( widn .. wid1 n -- ) n of -1 sets the minimum search order
+                if let Some(n) = self.pop_one("set-order") {
+                    if n == -1 {
+                        self.search_order = vec![FORTH_WORDLIST];
+                    } else if n < 0 || n as usize > self.stack.len() {
+                        self.throw("set-order", STACK_UNDERFLOW);
+                    } else {
+                        let wordlists = self.stack.split_off(self.stack.len() - n as usize);
+                        if let Some(bad) = wordlists.iter().find(|wid| !self.valid_wordlist(**wid))
+                        {
+                            self.msg.error("set-order", "Invalid wordlist", Some(bad));
+                        } else {
+                            self.search_order =
+                                wordlists.iter().rev().map(|wid| *wid as usize).collect();
+                        }
                     }
-                    "r/w" => {
-                        self.file_mode = FileMode::ReadWrite;
+                }
+            }
+            "get-current" => self.stack.push(self.current_wordlist as i64),
+            "set-current" => {
+                // ( wid -- ) new definitions go into wid
+                if let Some(wid) = self.pop_one("set-current") {
+                    if self.valid_wordlist(wid) {
+                        self.current_wordlist = wid as usize;
+                    } else {
+                        self.msg.error("set-current", "Invalid wordlist", Some(wid));
                     }
-                    "r/o" => {
-                        self.file_mode = FileMode::ReadOnly;
+                }
+            }
+            "definitions" => match self.search_order.first() {
+                // ( -- ) new definitions go into the first wordlist in the search order
+                Some(first) => self.current_wordlist = *first,
+                None => self
+                    .msg
+                    .error("definitions", "The search order is empty", None::<bool>),
+            },
+            "also" => match self.search_order.first() {
+                // ( -- ) duplicate the first wordlist in the search order
+                Some(first) => self.search_order.insert(0, *first),
+                None => self
+                    .msg
+                    .error("also", "The search order is empty", None::<bool>),
+            },
+            "previous" => {
+                // ( -- ) remove the first wordlist from the search order
+                if self.search_order.is_empty() {
+                    self.msg
+                        .error("previous", "The search order is empty", None::<bool>);
+                } else {
+                    self.search_order.remove(0);
+                }
+            }
+            "only" => self.search_order = vec![FORTH_WORDLIST],
+            "forth" => match self.search_order.first_mut() {
+                // ( -- ) replace the first wordlist in the search order with the forth wordlist
+                Some(first) => *first = FORTH_WORDLIST,
+                None => self.search_order.push(FORTH_WORDLIST),
+            },
+            "vocabulary" => {
+                // ( "name" -- ) the new word puts a new wordlist first in the search order
+                match self.parser.get_word() {
+                    Some(name) => {
+                        self.defined_words.push(HashMap::new());
+                        let wordlist = self.defined_words.len() - 1;
+                        self.define_word(&name, WordKind::Vocabulary(wordlist));
                     }
-                    "loaded" => {
-                        self.loaded();
+                    None => self.msg.error("vocabulary", "Name required", None::<bool>),
+                }
+            }
+            "order" => {
+                // ( -- ) print the search order and the current wordlist
+                let order: Vec<String> = self
+                    .search_order
+                    .iter()
+                    .map(|wordlist| self.wordlist_name(*wordlist))
+                    .collect();
+                println!(
+                    "Search order: {}  Definitions: {}",
+                    order.join(" "),
+                    self.wordlist_name(self.current_wordlist)
+                );
+            }
+            "defer!" => {
+                if let Some((deferred, action)) = self.pop_two("defer!") {
+                    self.defer_store("defer!", deferred, action);
+                }
+            }
+            "defer@" => {
+                if let Some(deferred) = self.pop_one("defer@") {
+                    self.defer_fetch("defer@", deferred);
+                }
+            }
+            "does>" => {
+                // the rest of this definition becomes the run time code of the last created word
+                if self.call_depth == 0 {
+                    self.msg.error(
+                        "does>",
+                        "Can only be used inside a definition",
+                        None::<bool>,
+                    );
+                } else if let Some(xt) = self.last_created {
+                    if let WordKind::Created(_, does) = &mut self.word_definitions[xt].kind {
+                        *does = Some((self.current_definition, program_counter));
                     }
-                    "dbg" => match self.stack.pop() {
-                        Some(0) => self.msg.set_level(DebugLevel::Error),
-                        Some(1) => self.msg.set_level(DebugLevel::Warning),
-                        Some(2) => self.msg.set_level(DebugLevel::Info),
-                        _ => self.msg.set_level(DebugLevel::Debug),
-                    },
-                    "debuglevel?" => {
-                        println!("DebugLevel is {:?}", self.msg.get_level());
+                    self.exit_definition("does>");
+                } else {
+                    self.msg
+                        .error("does>", "No word has been created", None::<bool>);
+                }
+            }
+            "immediate" => match self.word_definitions.last_mut() {
+                // ( -- ) make the most recent definition immediate
+                Some(word) if !matches!(word.kind, WordKind::Builtin) => word.immediate = true,
+                _ => self
+                    .msg
+                    .error("immediate", "No definition to change", None::<bool>),
+            },
+            "[" => {
+                // ( -- ) interpret the following words, in the middle of a definition
+                self.set_compile_mode(false);
+            }
+            "]" => {
+                // ( -- ) go back to compiling the definition
+                if self.new_word_name.is_empty() {
+                    self.msg
+                        .error("]", "No definition is being compiled", None::<bool>);
+                } else {
+                    self.set_compile_mode(true);
+                }
+            }
+            "literal" => {
+                // ( x -- ) compile x into the definition
+                if !self.compile_mode {
+                    self.msg
+                        .error("literal", "Can only be used while compiling", None::<bool>);
+                } else if let Some(x) = self.pop_one("literal") {
+                    self.new_word_definition.push(ForthToken::Integer(x));
+                }
+            }
+            "state" => self.stack.push(STATE_ADDRESS),
+            "char" => {
+                // ( "name" -- c ) the first character of the next word
+                match self.parser.get_word() {
+                    Some(name) => self.push_char("char", &name),
+                    None => self.msg.error("char", "Name required", None::<bool>),
+                }
+            }
+            "{:" | "locals|" => {
+                self.msg
+                    .error(op, "Can only be used inside a definition", None::<bool>);
+            }
+            "recurse" => {
+                self.msg.error(
+                    "recurse",
+                    "Can only be used inside a definition",
+                    None::<bool>,
+                );
+            }
+            "abort" => {
+                // empty the stack, reset any pending operations, and return to the prompt
+                self.throw("abort", ABORT);
+            }
+            "throw" => {
+                // ( n -- ) throw exception n, unless it is 0
+                if let Some(n) = self.pop_one("throw") {
+                    if n != 0 {
+                        self.throw("throw", n);
                     }
-                    ":" => {
-                        // Enter compile mode
-                        self.set_compile_mode(true);
+                }
+            }
+            "catch" => {
+                // ( xt -- 0 | n ) run xt, catching any exception it throws
+                if let Some(xt) = self.pop_one("catch") {
+                    if xt >= 0 && (xt as usize) < self.word_definitions.len() {
+                        self.catch(xt as usize);
+                    } else {
+                        self.throw("catch", UNDEFINED_WORD);
+                    }
+                }
+            }
+            "words" => self.print_words(""),
+            "words-like" => {
+                // ( "filter" -- ) the filter is read when words-like runs
+                match self.parser.get_word() {
+                    Some(filter) => self.print_words(&filter),
+                    None => self
+                        .msg
+                        .error("words-like", "Filter required", None::<bool>),
+                }
+            }
+            "traverse-wordlist" => {
+                // ( i*x xt wid -- j*x ) run xt ( k*x nt -- l*x flag ) on each word in wid,
+                // newest first, until it leaves false. A name token is the word's execution token
+                if let Some((wid, xt)) = self.pop_two("traverse-wordlist") {
+                    if !self.valid_wordlist(wid) {
+                        self.msg
+                            .error("traverse-wordlist", "Invalid wordlist", Some(wid));
+                    } else if xt < 0 || xt as usize >= self.word_definitions.len() {
+                        self.throw("traverse-wordlist", UNDEFINED_WORD);
+                    } else {
+                        let mut words: Vec<usize> =
+                            self.defined_words[wid as usize].values().copied().collect();
+                        words.sort_unstable_by(|a, b| b.cmp(a));
+                        for nt in words {
+                            self.stack.push(nt as i64);
+                            self.execute_xt(xt as usize);
+                            if self.abort_flag {
+                                break;
+                            }
+                            match self.pop_one("traverse-wordlist") {
+                                Some(0) | None => break,
+                                Some(_) => {}
+                            }
+                        }
                     }
-                    "step-on" => self.step_mode = true,
-                    "step-off" => self.step_mode = false,
-                    "bye" => {
-                        self.set_exit_flag();
+                }
+            }
+            "name>string" => {
+                // ( nt -- c-addr u ) the name goes in a transient buffer
+                if let Some(nt) = self.pop_one("name>string") {
+                    match self.word_definitions.get(nt as usize) {
+                        Some(word) if nt >= 0 => {
+                            let name = word.name.clone();
+                            let buffer =
+                                STRING_BUFFER_ADDRESS + self.next_buffer * STRING_BUFFER_SIZE;
+                            if let Some(address) = self.store_string(
+                                "name>string",
+                                name.as_bytes(),
+                                false,
+                                Some(buffer),
+                            ) {
+                                self.next_buffer = (self.next_buffer + 1) % STRING_BUFFERS;
+                                self.stack.push(address);
+                                self.stack.push(name.len() as i64);
+                            }
+                        }
+                        _ => self
+                            .msg
+                            .error("name>string", "Invalid name token", Some(nt)),
                     }
-                    // Add more operators as needed
-                    _ => {
-                        // It must be a defined word
-                        self.execute_definition();
+                }
+            }
+            "seeall" => {
+                for xt in self.current_words() {
+                    if !matches!(self.word_definitions[xt].kind, WordKind::Builtin) {
+                        let name = self.word_definitions[xt].name.clone();
+                        self.word_see(&name);
                     }
                 }
             }
+            "'" => {
+                // ( "name" -- xt ) the name is read when ' runs
+                match self.parser.get_word() {
+                    Some(name) => self.tick("'", &name),
+                    None => self.msg.error("'", "Name required", None::<bool>),
+                }
+            }
+            "execute" => {
+                // ( xt -- ) run the word whose execution token is on the stack
+                if let Some(xt) = self.pop_one("execute") {
+                    if xt >= 0 && (xt as usize) < self.word_definitions.len() {
+                        self.execute_xt(xt as usize);
+                    } else {
+                        self.throw("execute", UNDEFINED_WORD);
+                    }
+                }
+            }
+            ">body" => {
+                // ( xt -- addr ) the data space address of a created word
+                if let Some(xt) = self.pop_one(">body") {
+                    match self.word_definitions.get(xt as usize).map(|w| &w.kind) {
+                        Some(WordKind::Created(address, _)) if xt >= 0 => self.stack.push(*address),
+                        _ => self.throw(">body", NOT_CREATED),
+                    }
+                }
+            }
+            "stack-depth" => {
+                self.stack.push(self.stack.len() as i64);
+            }
+            "r/w" => {
+                self.file_mode = FileMode::ReadWrite;
+            }
+            "r/o" => {
+                self.file_mode = FileMode::ReadOnly;
+            }
+            "loaded" => {
+                self.loaded();
+            }
+            "dbg" => match self.stack.pop() {
+                Some(0) => self.msg.set_level(DebugLevel::Error),
+                Some(1) => self.msg.set_level(DebugLevel::Warning),
+                Some(2) => self.msg.set_level(DebugLevel::Info),
+                _ => self.msg.set_level(DebugLevel::Debug),
+            },
+            "debuglevel?" => {
+                println!("DebugLevel is {:?}", self.msg.get_level());
+            }
+            ":" => {
+                // Enter compile mode
+                self.new_word_start = self.here();
+                self.new_word_first_xt = self.word_definitions.len();
+                self.set_compile_mode(true);
+            }
+            "step-on" => self.step_mode = true,
+            "step-off" => self.step_mode = false,
+            "wrap-on" => self.wrap_arithmetic = true,
+            "wrap-off" => self.wrap_arithmetic = false,
+            "bye" => {
+                self.set_exit_flag();
+            }
+            // Add more operators as needed
+            _ => return false,
         }
-        (program_counter, jumped)
+        true
     }
 
-    fn execute_definition(&mut self) {
-        // execute a word defined in forth
-        // see if the word is in the dictionary.
-        // if so, iterate over the definition, using execute_token()
-        let mut program_counter: usize = 0;
-        let mut jumped = false;
-        match &self.token {
-            ForthToken::Operator(word_name) => {
-                if self.defined_words.contains_key(word_name) {
-                    let mut definition = self.defined_words[word_name.as_str()].clone();
-                    while program_counter < definition.len() {
-                        if self.abort_flag {
-                            definition.clear();
-                            self.stack.clear();
-                            self.control_stack.clear();
-                            self.abort_flag = false;
-                            break;
-                        } else {
-                            self.token = definition[program_counter].clone();
-                            (program_counter, jumped) = self.execute_token(program_counter, jumped);
-                        }
-                    }
-                } else if self.defined_variables.contains_key(word_name) {
-                    //  check for a variable
-                    self.stack.push(self.defined_variables[word_name]); // push the index on the stack
-                } else if self.defined_constants.contains_key(word_name) {
-                    //  check for a variable
-                    self.stack.push(self.defined_constants[word_name]); // push the index on the stack
-                } else {
+    fn execute_xt(&mut self, xt: usize) {
+        // run the word with execution token xt, whatever kind of word it is
+        match self.word_definitions[xt].kind {
+            WordKind::Builtin => {
+                let name = self.word_definitions[xt].name.clone();
+                if !self.execute_builtin(&name, 0) {
                     self.msg
-                        .error("execute_definition", "Undefined word", Some(word_name));
+                        .error(&name, "Can't be executed this way", None::<bool>);
+                }
+            }
+            WordKind::Colon(_) => self.run_definition(xt, 0),
+            WordKind::Variable(address) | WordKind::Created(address, None) => {
+                self.stack.push(address);
+            }
+            WordKind::Constant(value) => self.stack.push(value),
+            WordKind::FConstant(value) => self.float_stack.push(value),
+            WordKind::Created(address, Some((index, start))) => {
+                // push the data address, then run the does> code
+                self.stack.push(address);
+                self.run_definition(index, start);
+            }
+            WordKind::Value(x) => self.stack.push(x),
+            WordKind::Marker(..) => self.forget_from(xt),
+            WordKind::Vocabulary(wordlist) => match self.search_order.first_mut() {
+                Some(first) => *first = wordlist,
+                None => self.search_order.push(wordlist),
+            },
+            WordKind::TwoValue(x1, x2) => {
+                self.stack.push(x1);
+                self.stack.push(x2);
+            }
+            WordKind::Deferred(Some(action)) => {
+                // count the call, so a deferred word set to itself throws rather than recursing forever
+                if self.call_depth >= MAX_CALL_DEPTH {
+                    let name = self.word_definitions[xt].name.clone();
+                    self.throw(&name, RETURN_STACK_OVERFLOW);
                     return;
                 }
+                self.call_depth += 1;
+                self.execute_xt(action);
+                self.call_depth -= 1;
+            }
+            WordKind::Deferred(None) => {
+                // a deferred word that hasn't been set with is has no word to run
+                let name = self.word_definitions[xt].name.clone();
+                self.throw(&name, UNDEFINED_WORD);
+            }
+        }
+    }
+
+    fn find(&mut self, word: &str, name: &str) -> Option<usize> {
+        // the execution token of name, reporting an error if it isn't defined
+        let xt = self.find_word(name);
+        if xt.is_none() {
+            self.throw(&format!("{word} {name}"), UNDEFINED_WORD);
+        }
+        xt
+    }
+
+    fn immediate_word(&self, name: &str) -> Option<usize> {
+        // the execution token of name, if it is an immediate word
+        self.find_word(name)
+            .filter(|xt| self.word_definitions[*xt].immediate)
+    }
+
+    fn push_char(&mut self, word: &str, name: &str) {
+        match name.chars().next() {
+            Some(c) => self.stack.push(c as i64),
+            None => self.msg.error(word, "Character required", None::<bool>),
+        }
+    }
+
+    fn tick(&mut self, word: &str, name: &str) {
+        // push the execution token of name
+        if let Some(xt) = self.find(word, name) {
+            self.stack.push(xt as i64);
+        }
+    }
+
+    fn value_store(&mut self, word: &str, xt: usize, add: bool) {
+        // set a value or 2value from the stack, or add to a value
+        match self.word_definitions[xt].kind {
+            WordKind::Value(x) => {
+                if let Some(n) = self.pop_one(word) {
+                    let x = if add {
+                        self.arithmetic(word, x, n)
+                    } else {
+                        Some(n)
+                    };
+                    if let Some(x) = x {
+                        self.word_definitions[xt].kind = WordKind::Value(x);
+                    }
+                }
+            }
+            WordKind::TwoValue(_, _) if !add => {
+                if let Some((x2, x1)) = self.pop_two(word) {
+                    self.word_definitions[xt].kind = WordKind::TwoValue(x1, x2);
+                }
             }
             _ => {
-                self.msg
-                    .error("execute_definition", "Definition error", None::<bool>);
+                let name = self.word_definitions[xt].name.clone();
+                self.throw(&format!("{word} {name}"), ARGUMENT_TYPE_MISMATCH);
             }
         }
     }
 
+    fn defer_store(&mut self, word: &str, deferred: i64, action: i64) {
+        // make the deferred word run action
+        if action < 0 || action as usize >= self.word_definitions.len() {
+            self.throw(word, UNDEFINED_WORD);
+            return;
+        }
+        match self.word_definitions.get_mut(deferred as usize) {
+            Some(WordDefinition {
+                kind: WordKind::Deferred(target),
+                ..
+            }) => *target = Some(action as usize),
+            _ => self.throw(word, ARGUMENT_TYPE_MISMATCH),
+        }
+    }
+
+    fn defer_fetch(&mut self, word: &str, deferred: i64) {
+        // push the execution token a deferred word runs
+        match self
+            .word_definitions
+            .get(deferred as usize)
+            .map(|w| &w.kind)
+        {
+            Some(WordKind::Deferred(Some(action))) => self.stack.push(*action as i64),
+            Some(WordKind::Deferred(None)) => self.throw(word, UNDEFINED_WORD),
+            _ => self.throw(word, ARGUMENT_TYPE_MISMATCH),
+        }
+    }
+
+    fn current_words(&self) -> Vec<usize> {
+        // the execution tokens of the words that can be found by name, newest first
+        (0..self.word_definitions.len())
+            .rev()
+            .filter(|xt| self.find_word(&self.word_definitions[*xt].name) == Some(*xt))
+            .collect()
+    }
+
+    fn print_words(&self, filter: &str) {
+        // list the words containing filter by kind, newest first. Builtins are listed alphabetically.
+        let mut groups: Vec<(&str, Vec<&str>)> = [
+            "Colon definitions",
+            "Variables",
+            "Constants",
+            "Values",
+            "Created words",
+            "Deferred words",
+            "Markers",
+            "Vocabularies",
+            "Builtins",
+        ]
+        .iter()
+        .map(|group| (*group, Vec::new()))
+        .collect();
+        for xt in self.current_words() {
+            let word = &self.word_definitions[xt];
+            if word.name.contains(filter) {
+                let group = match word.kind {
+                    WordKind::Colon(_) => 0,
+                    WordKind::Variable(_) => 1,
+                    WordKind::Constant(_) | WordKind::FConstant(_) => 2,
+                    WordKind::Value(_) | WordKind::TwoValue(_, _) => 3,
+                    WordKind::Created(_, _) => 4,
+                    WordKind::Deferred(_) => 5,
+                    WordKind::Marker(..) => 6,
+                    WordKind::Vocabulary(_) => 7,
+                    WordKind::Builtin => 8,
+                };
+                groups[group].1.push(&word.name);
+            }
+        }
+        groups[8].1.reverse();
+        let mut total = 0;
+        for (group, names) in groups.iter().filter(|(_, names)| !names.is_empty()) {
+            println!("{group} ({}): {}", names.len(), names.join(" "));
+            total += names.len();
+        }
+        println!("Total: {total}");
+    }
+
+    fn run_definition(&mut self, index: usize, start: usize) {
+        // run the definition at index in word_definitions, from token start
+        let word_name = self.word_definitions[index].name.clone();
+        let mut definition = match &self.word_definitions[index].kind {
+            WordKind::Colon(definition) => definition.clone(),
+            _ => return,
+        };
+        if self.call_depth >= MAX_CALL_DEPTH {
+            self.throw(&word_name, RETURN_STACK_OVERFLOW);
+            return;
+        }
+        self.call_depth += 1;
+        let caller_definition = self.current_definition;
+        self.current_definition = index;
+        let mut program_counter = start;
+        let caller_return_base = self.return_base;
+        self.return_base = self.return_stack.len();
+        let caller_control_base = self.control_base;
+        self.control_base = self.control_stack.len();
+        let caller_locals_base = self.locals_base;
+        self.locals_base = self.locals.len();
+        while program_counter < definition.len() {
+            if self.abort_flag {
+                definition.clear();
+                if self.catch_frames.is_empty() {
+                    self.stack.clear();
+                    self.float_stack.clear();
+                    self.control_stack.clear();
+                    self.return_stack.clear();
+                    if self.call_depth == 1 {
+                        // we're back at the outermost definition, so the abort is finished
+                        self.abort_flag = false;
+                    }
+                }
+                break;
+            } else {
+                self.token = definition[program_counter].clone();
+                program_counter = self.execute_token(program_counter);
+                if self.return_flag {
+                    self.return_flag = false;
+                    break;
+                }
+            }
+        }
+        // discard loop frames left behind by exit
+        self.control_stack.truncate(self.control_base);
+        self.control_base = caller_control_base;
+        // anything this definition left on the return stack is an error,
+        // unless it is being unwound by an exception
+        if self.return_stack.len() > self.return_base && !self.abort_flag {
+            self.msg.error(
+                &word_name,
+                "Return stack imbalance at end of definition",
+                Some(self.return_stack.len() - self.return_base),
+            );
+            self.return_stack.truncate(self.return_base);
+        }
+        self.locals.truncate(self.locals_base);
+        self.locals_base = caller_locals_base;
+        self.return_base = caller_return_base;
+        self.current_definition = caller_definition;
+        self.call_depth -= 1;
+    }
+
+    fn exit_definition(&mut self, word: &str) {
+        // leave the current definition at the end of this token
+        if self.call_depth == 0 {
+            self.msg
+                .error(word, "Can only be used inside a definition", None::<bool>);
+        } else {
+            self.return_flag = true;
+        }
+    }
+
     pub fn load_file(&mut self, path: &String) -> bool {
         // read in a file of forth code using the provided path
         // returns false in case of error
@@ -752,7 +2571,10 @@ impl ForthInterpreter {
                     Some(mut previous_reader) => {
                         std::mem::swap(&mut previous_reader, &mut self.parser.reader);
                         loop {
-                            if self.process_token() {
+                            if self.thrown.is_some() {
+                                // an exception is on its way to a catch outside the file
+                                break;
+                            } else if self.process_token() {
                                 self.msg.debug("loaded", "processed", Some(&self.token));
                             } else {
                                 self.msg
@@ -781,54 +2603,215 @@ impl ForthInterpreter {
     }
 
     fn loaded(&mut self) {
-        // Load a file of forth code. Initial implementation is not intended to be recursive.
+        // ( c-addr u -- ) Load a file of forth code. Initial implementation is not intended to be recursive.
         // attempt to open the file, return an error if not possible
-        self.load_file(&self.text.clone());
+        if let Some(path) = self.pop_string("loaded") {
+            self.load_file(&String::from_utf8_lossy(&path).into_owned());
+        }
+    }
+
+    fn here(&self) -> i64 {
+        self.data_space.len() as i64
+    }
+
+    fn allot(&mut self, word: &str, n: i64) -> bool {
+        // grow (or with negative n, shrink) the data space, returning false if it can't
+        match self.here().checked_add(n) {
+            Some(new_here) if (0..=MAX_DATA_SPACE).contains(&new_here) => {
+                self.data_space.resize(new_here as usize, 0);
+                true
+            }
+            _ => {
+                self.throw(word, DICTIONARY_OVERFLOW);
+                false
+            }
+        }
+    }
+
+    fn align(&mut self) {
+        // make HERE a multiple of the cell size
+        let here = self.here();
+        self.allot("align", aligned(here).unwrap_or(here) - here);
+    }
+
+    fn address(&mut self, word: &str, addr: i64, len: i64) -> Option<usize> {
+        // check that the len bytes starting at addr are in the data space
+        match addr.checked_add(len) {
+            Some(end) if addr >= 0 && len >= 0 && end <= self.here() => Some(addr as usize),
+            _ => {
+                self.throw(word, INVALID_MEMORY_ADDRESS);
+                None
+            }
+        }
+    }
+
+    fn string_at(&mut self, word: &str, addr: i64, len: i64) -> Option<Vec<u8>> {
+        // the len characters starting at addr
+        let address = self.address(word, addr, len)?;
+        Some(self.data_space[address..address + len as usize].to_vec())
+    }
+
+    fn pop_string(&mut self, word: &str) -> Option<Vec<u8>> {
+        // ( c-addr u -- ) the string whose address and length are on the stack
+        let (len, addr) = self.pop_two(word)?;
+        self.string_at(word, addr, len)
+    }
+
+    fn store_string(
+        &mut self,
+        word: &str,
+        text: &[u8],
+        counted: bool,
+        buffer: Option<i64>,
+    ) -> Option<i64> {
+        // copy text into a transient buffer, or at HERE if there is no buffer,
+        // returning its address. A counted string starts with its length.
+        let mut bytes = text.to_vec();
+        if counted {
+            if bytes.len() > u8::MAX as usize {
+                self.throw(word, PARSED_STRING_OVERFLOW);
+                return None;
+            }
+            bytes.insert(0, bytes.len() as u8);
+        }
+        let address = match buffer {
+            Some(_) if bytes.len() > STRING_BUFFER_SIZE as usize => {
+                self.throw(word, PARSED_STRING_OVERFLOW);
+                return None;
+            }
+            Some(buffer) => buffer,
+            None => {
+                let here = self.here();
+                if !self.allot(word, bytes.len() as i64) {
+                    return None;
+                }
+                here
+            }
+        };
+        self.data_space[address as usize..address as usize + bytes.len()].copy_from_slice(&bytes);
+        Some(address)
+    }
+
+    fn hold(&mut self, word: &str, text: &[u8]) -> bool {
+        // add text to the start of the pictured numeric output
+        let start = self.hold_start - text.len() as i64;
+        if start < HOLD_BUFFER_END - HOLD_BUFFER_SIZE {
+            self.throw(word, PICTURED_OUTPUT_OVERFLOW);
+            return false;
+        }
+        self.data_space[start as usize..self.hold_start as usize].copy_from_slice(text);
+        self.hold_start = start;
+        true
+    }
+
+    fn cell_at(&self, address: usize) -> i64 {
+        // the cell at an address already checked by address()
+        let mut bytes = [0; CELL_SIZE as usize];
+        bytes.copy_from_slice(&self.data_space[address..address + CELL_SIZE as usize]);
+        i64::from_le_bytes(bytes)
+    }
+
+    fn set_cell_at(&mut self, address: usize, value: i64) {
+        self.data_space[address..address + CELL_SIZE as usize]
+            .copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn fetch_cell(&mut self, word: &str, addr: i64) -> Option<i64> {
+        let address = self.address(word, addr, CELL_SIZE)?;
+        Some(self.cell_at(address))
+    }
+
+    fn store_cell(&mut self, word: &str, addr: i64, value: i64) {
+        if let Some(address) = self.address(word, addr, CELL_SIZE) {
+            self.set_cell_at(address, value);
+        }
     }
 
-    fn variable_see(&self, name: &str, index: i64) {
-        let idx = index.max(0) as usize;
-        let value = self.variable_stack[idx];
-        println!("Variable {name}: {value}");
+    fn variable_see(&self, name: &str, address: i64) {
+        // a variable's cell is in the data space for as long as the variable exists
+        let value = self.cell_at(address as usize);
+        println!("Variable {name}: {}", self.number_text(value as i128));
+    }
+
+    fn print_definition(&self, index: usize, start: usize) {
+        // print the tokens of a definition, from token start
+        let WordKind::Colon(definition) = &self.word_definitions[index].kind else {
+            return;
+        };
+        for word in &definition[start..] {
+            match word {
+                ForthToken::Integer(num) => print!("{} ", self.number_text(*num as i128)),
+                ForthToken::Text(word, text, ..) => print!("{word} {text}\" "),
+                ForthToken::Call(call) if *call == index => print!("recurse "),
+                ForthToken::Call(call) => print!("{} ", self.word_definitions[*call].name),
+                ForthToken::Locals(names, initialized) => {
+                    print!("{} ", locals_text(names, *initialized));
+                }
+                ForthToken::Local(info) => print!("{} ", local_text(info)),
+                ForthToken::Float(num) => print!("f{num} "),
+                ForthToken::Operator(op) => print!("{op} "),
+                ForthToken::Branch(info) => {
+                    print!("{}:{}:{} ", info.word, info.offset, info.branch_id);
+                }
+                ForthToken::Forward(info) => {
+                    print!("{}{} ", info.word, info.tail);
+                }
+                ForthToken::Empty => print!("ForthToken::Empty "),
+            }
+        }
+        if self.word_definitions[index].immediate {
+            println!("; immediate");
+        } else {
+            println!(";");
+        }
     }
 
     fn word_see(&self, name: &str) {
         // if it's a word:
-        match self.defined_words.get(name) {
-            Some(definition) => {
+        let Some(xt) = self.find_word(name) else {
+            self.msg.warning("SEE", "Word not found", Some(name));
+            return;
+        };
+        match self.word_definitions[xt].kind {
+            WordKind::Colon(_) => {
                 print!(": {name} ");
-                for word in definition {
-                    match word {
-                        ForthToken::Integer(num) => print!("{num} "),
-                        ForthToken::Float(num) => print!("f{num} "),
-                        ForthToken::Operator(op) => print!("{op} "),
-                        ForthToken::Branch(info) => {
-                            print!("{}:{}:{} ", info.word, info.offset, info.branch_id);
-                        }
-                        ForthToken::Forward(info) => {
-                            print!("{}{} ", info.word, info.tail);
-                        }
-                        ForthToken::Empty => print!("ForthToken::Empty "),
-                    }
-                }
-                println!(";");
+                self.print_definition(xt, 0);
             }
-            None => {
-                // check to see if it's a built-in
-                let doc_string = self.builtin_doc.get(name);
-                match doc_string {
-                    Some(doc_string) => {
-                        println!("Builtin: {name} {doc_string}");
-                    }
-                    None => self.msg.warning("SEE", "Word not found", Some(name)),
+            WordKind::Variable(address) => self.variable_see(name, address),
+            WordKind::Constant(value) => {
+                println!("Constant {name}: {}", self.number_text(value as i128))
+            }
+            WordKind::FConstant(value) => println!("FConstant {name}: {value:?}"),
+            WordKind::Created(address, Some((index, start))) => {
+                print!("create {name} ( {address} ) does> ");
+                self.print_definition(index, start);
+            }
+            WordKind::Created(address, None) => println!("create {name} ( {address} )"),
+            WordKind::Value(x) => println!("Value {name}: {}", self.number_text(x as i128)),
+            WordKind::Marker(..) => println!("marker {name}"),
+            WordKind::Vocabulary(wordlist) => println!("vocabulary {name} ( wordlist {wordlist} )"),
+            WordKind::TwoValue(x1, x2) => println!(
+                "2Value {name}: {} {}",
+                self.number_text(x1 as i128),
+                self.number_text(x2 as i128)
+            ),
+            WordKind::Deferred(Some(action)) => {
+                println!("defer {name} is {}", self.word_definitions[action].name)
+            }
+            WordKind::Deferred(None) => println!("defer {name} ( not set )"),
+            WordKind::Builtin => {
+                if let Some(doc_string) = self.builtin_doc.get(name) {
+                    println!("Builtin: {name} {doc_string}");
                 }
             }
         }
     }
 
     fn get_stack(&self) -> String {
-        if self.show_stack {
-            format!("{:?}", self.stack)
+        if self.show_stack && !self.float_stack.is_empty() {
+            format!("{} F: {:?}", self.stack_text(), self.float_stack)
+        } else if self.show_stack {
+            self.stack_text()
         } else {
             "".to_owned()
         }
@@ -838,14 +2821,26 @@ impl ForthInterpreter {
         println!("Calculation Stack: {}", self.get_stack());
     }
 
+    fn print_float_stack(&self) {
+        println!("Float       stack: {:?}", self.float_stack);
+    }
+
     fn print_control_stack(&self) {
         println!("Control     stack: {:?}", self.control_stack);
     }
 
+    fn print_return_stack(&self) {
+        println!("Return      stack: {:?}", self.return_stack);
+    }
+
     fn print_variables(&self) {
         println!("Variables:");
-        for (name, val) in self.defined_variables.iter() {
-            println!("{name} = {val}");
+        for xt in self.current_words() {
+            let name = &self.word_definitions[xt].name;
+            if let WordKind::Variable(address) = self.word_definitions[xt].kind {
+                let value = self.cell_at(address as usize);
+                println!("{name} = {}", self.number_text(value as i128));
+            }
         }
     }
 
@@ -853,9 +2848,15 @@ impl ForthInterpreter {
         // controls step / debug functions
         if self.step_mode {
             match &self.token {
-                ForthToken::Integer(num) => print!("{num}: Step> "),
+                ForthToken::Integer(num) => print!("{}: Step> ", self.number_text(*num as i128)),
                 ForthToken::Float(num) => print!("f{num}: Step> "),
                 ForthToken::Operator(op) => print!("{op}: Step> "),
+                ForthToken::Call(index) => print!("{}: Step> ", self.word_definitions[*index].name),
+                ForthToken::Locals(names, initialized) => {
+                    print!("{}: Step> ", locals_text(names, *initialized));
+                }
+                ForthToken::Text(word, text, ..) => print!("{word} {text}\": Step> "),
+                ForthToken::Local(info) => print!("{}: Step> ", local_text(info)),
                 ForthToken::Branch(info) => {
                     print!("{}:{}:{}: Step> ", info.word, info.offset, info.branch_id);
                 }
@@ -868,11 +2869,14 @@ impl ForthInterpreter {
             match self.parser.reader.read_char() {
                 Some('s') => {
                     self.print_stack();
+                    self.print_float_stack();
                     self.print_control_stack();
+                    self.print_return_stack();
                 }
                 Some('v') => self.print_variables(),
                 Some('a') => {
                     self.print_stack();
+                    self.print_float_stack();
                     self.print_variables();
                 }
                 Some('c') => self.step_mode = false,
@@ -881,3 +2885,57 @@ impl ForthInterpreter {
         }
     }
 }
+
+fn aligned(address: i64) -> Option<i64> {
+    // round address up to a multiple of the cell size, or None if that is too big for a cell
+    address.checked_add((CELL_SIZE - address.rem_euclid(CELL_SIZE)) % CELL_SIZE)
+}
+
+fn string_text(tail: &str) -> &str {
+    // the text of a string token's tail, which starts with a space and ends with the quote
+    tail.get(1..tail.len().saturating_sub(1)).unwrap_or("")
+}
+
+fn unsigned_text(mut n: u128, base: u32) -> String {
+    // n written in base, which is between 2 and 36
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(n % base as u128) as usize]);
+        n /= base as u128;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap_or_default()
+}
+
+fn string_bytes(word: &str, tail: &str) -> Vec<u8> {
+    // the characters of the string read by word, with escapes replaced if it is s\"
+    if word == "s\\\"" {
+        unescape(string_text(tail))
+    } else {
+        string_text(tail).as_bytes().to_vec()
+    }
+}
+
+fn locals_text(names: &[String], initialized: usize) -> String {
+    // a locals declaration as it would be written
+    let mut text = "{:".to_owned();
+    for (slot, name) in names.iter().enumerate() {
+        if slot == initialized {
+            text.push_str(" |");
+        }
+        text.push(' ');
+        text.push_str(name);
+    }
+    text + " :}"
+}
+
+fn local_text(info: &LocalInfo) -> String {
+    if info.word.is_empty() {
+        info.name.clone()
+    } else {
+        format!("{} {}", info.word, info.name)
+    }
+}
@@ -8,14 +8,23 @@ mod messages;
 mod reader;
 mod tokenizer;
 
+use std::thread;
+
 use config::Config;
 
+// Definitions call each other recursively, so the interpreter needs a deep stack
+const INTERPRETER_STACK_SIZE: usize = 256 * 1024 * 1024;
+
 fn main() {
     let mut config = Config::new();
     config.process_args();
 
     if config.run {
-        config.run_forth();
+        let interpreter = thread::Builder::new()
+            .stack_size(INTERPRETER_STACK_SIZE)
+            .spawn(move || config.run_forth())
+            .expect("unable to start the interpreter thread");
+        interpreter.join().unwrap();
     } else {
         config.exit()
     }
@@ -58,6 +58,14 @@ impl Msg {
         }
     }
 
+    pub fn notice<T: Debug>(&self, context: &str, text: &str, val: Option<T>) {
+        // shown at every level, like errors, for things that aren't errors but shouldn't go unseen
+        match val {
+            Some(val) => println!("NOTICE: {context}: {text}: {:?}", val),
+            None => println!("NOTICE: {context}: {text}"),
+        }
+    }
+
     pub fn error<T: Debug>(&self, context: &str, text: &str, val: Option<T>) {
         match val {
             Some(val) => println!("ERROR: {context}: {text}: {:?}", val),
@@ -65,3 +73,69 @@ impl Msg {
         }
     }
 }
+
+pub fn throw_message(code: i64) -> Option<&'static str> {
+    // the meaning of a standard throw code
+    let text = match code {
+        -1 => "Aborted",
+        -2 => "Aborted with a message",
+        -3 => "Stack overflow",
+        -4 => "Stack underflow",
+        -5 => "Return stack overflow",
+        -6 => "Return stack underflow",
+        -7 => "Do-loops nested too deeply",
+        -8 => "Dictionary overflow",
+        -9 => "Invalid memory address",
+        -10 => "Division by zero",
+        -11 => "Result out of range",
+        -12 => "Argument type mismatch",
+        -13 => "Undefined word",
+        -14 => "Interpreting a compile-only word",
+        -15 => "Invalid forget",
+        -16 => "Attempt to use zero-length string as a name",
+        -17 => "Pictured numeric output string overflow",
+        -18 => "Parsed string overflow",
+        -19 => "Definition name too long",
+        -20 => "Write to a read-only location",
+        -21 => "Unsupported operation",
+        -22 => "Control structure mismatch",
+        -23 => "Address alignment exception",
+        -24 => "Invalid numeric argument",
+        -25 => "Return stack imbalance",
+        -26 => "Loop parameters unavailable",
+        -27 => "Invalid recursion",
+        -28 => "User interrupt",
+        -29 => "Compiler nesting",
+        -30 => "Obsolescent feature",
+        -31 => ">body used on non-created definition",
+        -32 => "Invalid name argument",
+        -33 => "Block read exception",
+        -34 => "Block write exception",
+        -35 => "Invalid block number",
+        -36 => "Invalid file position",
+        -37 => "File I/O exception",
+        -38 => "Non-existent file",
+        -39 => "Unexpected end of file",
+        -40 => "Invalid base for floating point conversion",
+        -41 => "Loss of precision",
+        -42 => "Floating point divide by zero",
+        -43 => "Floating point result out of range",
+        -44 => "Float stack overflow",
+        -45 => "Float stack underflow",
+        -46 => "Floating point invalid argument",
+        -47 => "Compilation wordlist deleted",
+        -48 => "Invalid postpone",
+        -49 => "Search order overflow",
+        -50 => "Search order underflow",
+        -51 => "Compilation wordlist changed",
+        -52 => "Control-flow stack overflow",
+        -53 => "Exception stack overflow",
+        -54 => "Floating point underflow",
+        -55 => "Floating point unidentified fault",
+        -56 => "Quit",
+        -57 => "Exception in sending or receiving a character",
+        -58 => "[if], [else], or [then] exception",
+        _ => return None,
+    };
+    Some(text)
+}
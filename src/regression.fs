@@ -49,6 +49,158 @@ variable test-num 0 test-num !
             i . 
     loop ;
 
+: sum-to ( n -- sum ) 0 swap begin dup while tuck + swap 1- repeat drop ;
+: until-test ( n -- p ) 1 begin 2 * 2dup < until nip ;
+: again-test ( n -- n' ) 10 0 do begin 1+ dup 5 mod 0= if leave then again loop ;
+: begin-do-test ( -- n ) 0 3 0 do 0 begin 1+ dup 4 = until + loop ;
+: do-begin-test ( n -- n' ) begin dup 10 < while 2 0 do 1+ loop repeat ;
+: smallest-divisor ( n -- d ) 2 begin 2dup > while 2dup mod while 1+ repeat nip else drop then ;
+: nested-if-test ( a b -- n ) if if 1 else 2 then else if 3 else 4 then then ;
+: two-leave-test ( n -- i ) 10 0 do dup i = if drop i leave then i 7 = if drop -1 leave then loop ;
+: r-fetch ( a -- a a ) >r r@ r> ;
+: 2r-test ( a b -- b a ) 2>r 2r@ nip 2r> drop ;
+: 2r-order ( a b -- a ) 2>r r> drop r> ;
+: rdrop-test ( a b -- a ) >r rdrop ;
+: r-loop ( -- sum ) 0 5 0 do i >r i + r> + loop ;
+: r-nested ( a -- a ) >r r-fetch drop r> ;
+: fac-r ( n -- n! ) dup 2 < if drop 1 else dup 1- recurse * then ;
+: count-down ( n -- 0 ) dup if 1- recurse then [ variable count-var ] ;
+: sum-down ( n -- sum ) dup if dup 1- sum-down + then [ variable sum-var ] ;
+: countdown ( n -- 0 ) dup if 1- countdown then ;
+: twice ( n -- 2n ) 2 * ;
+: twice ( n -- 4n ) twice twice ;
+: exit-test ( n -- n' ) dup 0< if exit then 100 + ;
+: exit-in-loop ( n -- i ) 5 0 do i over = if drop i exit then loop drop -1 ;
+: loop-caller ( -- sum ) 0 3 0 do i exit-in-loop i + + loop ;
+: ?exit-test ( n -- n' ) dup ?exit 1+ ;
+: unloop-test ( -- n ) 10 0 do i 3 = if i unloop exit then loop -1 ;
+: unloop-twice ( -- ) 3 0 do unloop loop ;
+: inner-unloop ( -- ) unloop ;
+: outer-unloop ( -- ) 3 0 do inner-unloop loop ;
+: classify ( n -- m ) case 1 of 10 endof 2 of 20 endof 3 of 30 endof 99 swap endcase ;
+: nested-case ( m n -- k ) swap case 
+        1 of case 1 of 11 endof 2 of 12 endof 10 swap endcase endof 
+        2 of dup 0= if drop 20 else 21 + then endof
+        nip 0 swap
+    endcase ;
+: case-loop ( -- sum ) 0 5 0 do i case 1 of 1 + endof 3 of 3 + leave endof endcase loop ;
+: endif-test ( b -- n ) if 1 else 2 endif ;
+: array ( n -- ) create cells allot does> ( i -- addr ) swap cells + ;
+: konst ( n -- ) create , does> @ ;
+: counter ( -- ) create 0 , does> dup 1 swap +! @ ;
+: make-var ( -- ) create 0 , ;
+: xt-add1 ( n -- n+1 ) 1 + ;
+: xt-double ( n -- 2n ) 2 * ;
+create xt-table ' xt-add1 , ' xt-double , ' dup ,
+: xt-apply ( n i -- n' ) cells xt-table + @ execute ;
+: xt-compiled ( -- xt ) ['] xt-double ;
+: xt-inc ( n -- n+1 ) 1 + ;
+' xt-inc constant old-inc
+: xt-inc ( n -- n+2 ) 2 + ;
+defer handler
+: on-wash ( -- n ) 1 ;
+: on-rinse ( -- n ) 2 ;
+: run-handler ( -- n ) handler ;
+: set-rinse ( -- ) ['] on-rinse is handler ;
+: handler-action ( -- xt ) action-of handler ;
+' on-wash is handler
+10 value ten
+: ten+ ( n -- n' ) ten + ;
+: set-ten ( n -- ) to ten ;
+: bump-ten ( n -- ) +to ten ;
+1 2 2value pair
+: set-pair ( a b -- ) to pair ;
+: body-bad ( -- ) ['] xt-inc >body ;
+: execute-bad ( -- ) -1 execute ;
+: catch-bad ( -- ) -1 catch ;
+defer unset-handler
+: to-bad ( -- ) 1 to ten+ ;
+: defer-bad ( -- ) ['] on-wash ['] ten defer! ;
+: loc-swap {: a b -- b a :} b a ;
+: loc-uninit {: a | c -- n :} a 2 * to c c 1 + ;
+: loc-old ( a b -- a-b ) locals| x y | y x - ;
+: loc-fac {: n -- n! :} n 2 < if 1 else n 1- recurse n * then ;
+: loc-sum {: n | acc :} n 0 do i +to acc loop acc ;
+: loc-shadow {: dup :} dup dup + ;
+: loc-outer {: a b :} a b loc-swap drop a b + + ;
+: lit-sum ( -- n ) [ 3 4 + ] literal ;
+: five-now ( -- 5 ) 5 ; immediate
+: use-five ( -- 5 ) five-now literal ;
+: my-if postpone if ; immediate
+: my-then postpone then ; immediate
+: my-abs ( n -- |n| ) dup 0< my-if -1 * my-then ;
+: compile-dup postpone dup ; immediate
+: triple-dup ( n -- n n n ) compile-dup compile-dup ;
+: lit5 5 postpone literal ; immediate
+: use-lit5 ( -- 5 ) lit5 ;
+: state? state @ postpone literal ; immediate
+: compiled-state ( -- b ) state? ;
+: char-test ( -- c ) [char] A ;
+: fsquare ( F: r -- r*r ) fdup f* ;
+: fhalf ( F: r -- r/2 ) 0.5 f* ;
+fvariable fv
+2.0 fconstant ftwo
+: order-depth ( -- n ) get-order dup >r 0 do drop loop r> ;
+: secret ( -- n ) 1 ;
+vocabulary hidden
+also hidden definitions
+: secret ( -- n ) 42 ;
+: hidden-double ( n -- 2n ) 2 * ;
+forth-wordlist set-current
+: reveal ( -- n ) secret hidden-double ;
+previous
+wordlist constant scratch-wl
+scratch-wl set-current
+: in-scratch ( -- n ) 7 ;
+forth-wordlist set-current
+variable cells-count
+: cells ( n -- n*8 ) 1 cells-count +! cells ;
+: throws-99 ( -- ) 5 6 -99 throw 7 ;
+: no-throw ( -- n ) 42 0 throw ;
+: catch-99 ( -- n ) ['] throws-99 catch 100 + ;
+: nested-catch ( -- n 0 ) ['] catch-99 catch ;
+: abort-if ( b -- ) abort" abort-if failed" ;
+: divide-safely ( n1 n2 -- n ) ['] / catch if drop drop 0 then ;
+: return-throw ( -- ) 1 >r 2 >r -3 throw ;
+: loop-catch ( -- n ) 0 5 0 do ['] throws-99 catch + loop ;
+: greeting ( -- c-addr u ) s" hello world" ;
+: counted-greeting ( -- c-addr ) c" hello" ;
+create string-buffer 16 allot
+: huge-allot ( -- ) 9223372036854775807 allot ;
+: huge-aligned ( -- a ) 9223372036854775807 aligned ;
+: huge-2@ ( -- x1 x2 ) 9223372036854775807 2@ ;
+: bad-fetch ( -- x ) -1 @ ;
+: fade ( -- n ) 7 ;
+: add-one ( n -- n+1 ) 1 + ;
+: literal-bad [ ] literal ;
+: after-literal-bad ( -- n ) 7 ;
+: divide-bad 1 [ 1 0 / ] 2 ;
+: after-divide-bad ( -- n ) 8 ;
+variable here-before here here-before !
+: string-bad s" hello" if ;
+here here-before @ = constant string-bad-freed
+wordlist constant ordered
+get-current ordered set-current
+: older ( -- ) ;
+: newer ( -- ) ;
+: newest ( -- ) ;
+set-current
+: all-names ( nt -- nt true ) true ;
+: first-name ( nt -- nt false ) false ;
+: divide-by-zero ( -- n ) 1 0 / ;
+: mod-by-zero ( -- n ) 1 0 mod ;
+: add-overflow ( -- n ) 9223372036854775807 1 + ;
+: multiply-overflow ( -- n ) 4611686018427387904 2 * ;
+9223372036854775807 value big-value
+: value-overflow ( -- ) 1 +to big-value ;
+: local-overflow ( n -- ) {: x :} 1 +to x ;
+: loop-overflow ( -- ) 9223372036854775807 9223372036854775806 do 2 +loop ;
+: smallest-sm/rem ( -- n1 n2 ) 0 -9223372036854775808 -1 sm/rem ;
+: smallest-fm/mod ( -- n1 n2 ) 0 -9223372036854775808 -1 fm/mod ;
+: escaped ( -- c-addr u ) s\" a\tb\"c\\" ;
+: money ( n -- c-addr u ) s>d <# # # [char] . hold #s #> ;
+: signed ( n -- c-addr u ) dup >r abs s>d <# #s r> sign #> ;
+
 ."         Clear has to be the first test"
 1 2 3 4 5 clear test-none
 
@@ -76,6 +228,25 @@ variable test-num 0 test-num !
 0 0 0 loop-test test-single
 1 64 10 1 loop+test * * test-dual
 -1 0 5 -1 leave-test test-dual
+2 2 two-leave-test test-single
+-1 9 two-leave-test test-single
+
+."                Indefinite loops"
+55 10 sum-to test-single
+0 0 sum-to test-single
+128 100 until-test test-single
+5 0 again-test test-single
+12 begin-do-test test-single
+10 0 do-begin-test test-single
+11 11 do-begin-test test-single
+3 15 smallest-divisor test-single
+7 7 smallest-divisor test-single
+1 1 1 nested-if-test test-single
+2 0 1 nested-if-test test-single
+3 1 0 nested-if-test test-single
+4 0 0 nested-if-test test-single
+1 true endif-test test-single
+2 false endif-test test-single
 
 
 ."         Arithmetic"
@@ -84,6 +255,45 @@ variable test-num 0 test-num !
 -20 2 -10 * test-single
 4 12 3 / test-single
 
+."         Return stack"
+5 5 5 r-fetch test-dual
+7 4 4 7 2r-test test-dual
+3 3 4 2r-order test-single
+1 1 2 rdrop-test test-single
+20 r-loop test-single
+9 9 r-nested test-single
+
+."         Recursion"
+120 5 fac-r test-single
+1 0 fac-r test-single
+0 3 count-down test-single
+6 3 sum-down test-single
+0 5 countdown test-single
+20 5 twice test-single
+
+."         Exit"
+-5 -5 exit-test test-single
+105 5 exit-test test-single
+3 3 exit-in-loop test-single
+-1 7 exit-in-loop test-single
+6 loop-caller test-single
+5 5 ?exit-test test-single
+1 0 ?exit-test test-single
+3 unloop-test test-single
+-26 ' unloop-twice catch test-single
+-26 ' outer-unloop catch test-single
+
+."         Case"
+10 1 classify test-single
+30 3 classify test-single
+99 7 classify test-single
+12 1 2 nested-case test-single
+10 1 5 nested-case test-single
+20 2 0 nested-case test-single
+25 2 4 nested-case test-single
+0 3 3 nested-case test-single
+4 case-loop test-single
+
 ."         Logic"
 -1 true test-single
 0 false test-single
@@ -123,6 +333,286 @@ false 55 0< test-single
 42 variable y 40 y ! 2 y +! y @ test-single
 42 variable z 42 z ! z ? z @ test-single
 
+."        Memory"
+variable buf 4 cells allot
+10 here 10 allot here swap - test-single
+24 3 cells test-single
+8 buf cell+ buf - test-single
+5 5 chars test-single
+16 9 aligned test-single
+16 16 aligned test-single
+65 65 buf c! buf c@ test-single
+44 300 buf c! buf c@ test-single
+1 2 1 2 buf 2! buf 2@ test-dual
+2 1 2 buf 2! buf @ test-single
+42 here 42 , @ test-single
+7 here 7 c, c@ test-single
+-8 ' huge-allot catch test-single
+-9 ' huge-aligned catch test-single
+-9 ' huge-2@ catch test-single
+-9 ' bad-fetch catch test-single
+65 buf 8 65 fill buf 7 + c@ test-single
+0 buf 8 erase buf @ test-single
+99 99 buf ! buf buf cell+ 8 move buf cell+ @ test-single
+3 1 buf 2 cells + ! 2 buf 2 cells + +! buf 2 cells + @ test-single
+
+."        Create and does>"
+5 array arr
+42 konst answer
+counter clicks
+make-var mv
+create table 1 , 2 , 3 ,
+42 answer test-single
+77 77 3 arr ! 3 arr @ test-single
+8 1 arr 0 arr - test-single
+2 table cell+ @ test-single
+2 clicks drop clicks test-single
+9 9 mv ! mv @ test-single
+0 here create empty empty - test-single
+
+."        Execution tokens"
+6 5 ' xt-add1 execute test-single
+10 2 5 ' * execute test-single
+8 4 1 xt-apply test-single
+3 3 2 xt-apply drop test-single
+14 7 xt-compiled execute test-single
+11 10 old-inc execute test-single
+12 10 xt-inc test-single
+0 table ' table >body - test-single
+42 ' answer execute test-single
+
+."        Deferred words"
+1 handler test-single
+1 run-handler test-single
+' on-wash handler-action test-single
+2 set-rinse run-handler test-single
+' on-rinse ' handler defer@ test-single
+' on-wash ' handler defer! 1 handler test-single
+
+."        Values"
+10 ten test-single
+15 5 ten+ test-single
+20 20 set-ten ten test-single
+25 5 bump-ten ten test-single
+30 30 to ten ten test-single
+32 2 +to ten ten test-single
+1 2 pair test-dual
+3 4 3 4 set-pair pair test-dual
+5 6 5 6 to pair pair test-dual
+
+."        Locals"
+2 1 1 2 loc-swap test-dual
+7 3 loc-uninit test-single
+7 10 3 loc-old test-single
+120 5 loc-fac test-single
+10 5 loc-sum test-single
+6 3 loc-shadow test-single
+5 1 2 loc-outer test-single
+
+."        Compile time words"
+7 lit-sum test-single
+5 use-five test-single
+5 -5 my-abs test-single
+5 5 my-abs test-single
+9 3 triple-dup + + test-single
+5 use-lit5 test-single
+-1 compiled-state test-single
+0 state @ test-single
+65 char-test test-single
+97 char abc test-single
+
+."        Redefining builtins"
+24 3 cells test-single
+1 0 cells-count ! 3 cells drop cells-count @ test-single
+0 0 cells-count ! 2 arr drop cells-count @ test-single \ arr was compiled with the builtin cells
+
+."        Marker and forget"
+: shadowed ( -- n ) 1 ;
+marker -shadow
+: shadowed ( -- n ) 2 ;
+2 shadowed test-single
+-shadow
+1 shadowed test-single
+-1 here marker -space variable scratch 10 allot -space here = test-single
+: forget-me ( -- n ) 1 ;
+: forget-me ( -- n ) 2 ;
+forget forget-me
+1 forget-me test-single
+-1 here : with-string ( -- ) s" text" 2drop ; forget with-string here = test-single
+1 wordlist marker -lists wordlist drop -lists wordlist swap - test-single
+defer forward
+marker -target
+: target ( -- n ) 3 ;
+' target is forward
+3 forward test-single
+-target
+defer endless
+' endless is endless
+-5 ' endless catch test-single
+
+."        Wordlists"
+1 order-depth test-single
+0 1 get-order test-dual
+1 secret test-single
+84 reveal test-single
+2 also order-depth previous test-single
+1 -1 set-order order-depth test-single
+7 get-order scratch-wl swap 1+ set-order in-scratch previous test-single
+0 get-current test-single
+42 also hidden secret previous test-single
+1 marker -order also hidden -order order-depth test-single
+
+."        Floating point"
+3 1.5 1.5 f+ f>s test-single
+2 7.0 3.0 f- 2.0 f/ f>s test-single
+-1 1.0 2.0 f< test-single
+0 2.0 1.0 f< test-single
+-1 0.0 f0= test-single
+5 25.0 fsqrt f>s test-single
+8 2.0 3.0 f** f>s test-single
+7 7 s>f f>s test-single
+-3 -3.7 f>s test-single
+5 2.5 fv f! fv f@ fdup f+ f>s test-single
+4 ftwo ftwo f* f>s test-single
+2 1.0 2.0 fswap fdrop f>s test-single
+1 1.0 2.0 fover f>s fdrop fdrop test-single
+3 3.0 fexp fln 0.5 f+ f>s test-single
+0 0.0 fsin f>s test-single
+1 0.0 fcos f>s test-single
+9 3.0 fsquare f>s test-single
+2 4.0 fhalf f>s test-single
+
+."        Double numbers"
+-5 -1 -5 s>d test-dual
+7 0 7 s>d test-dual
+-6 -1 2 -3 m* test-dual
+-9223372036709301616 0 3037000500 3037000500 m* test-dual
+1 -2 -1 -1 um* test-dual
+1 2 7 0 3 um/mod test-dual
+-1 -2 -7 s>d 3 sm/rem test-dual
+2 -3 -7 s>d 3 fm/mod test-dual
+8000000000 4000000000 4000000000 2000000000 */ test-single
+2 3 4 5 6 */mod test-dual
+3 0 1 0 2 0 d+ test-dual
+0 1 -1 0 1 0 d+ test-dual
+-1 -1 0 0 1 0 d- test-dual
+-5 -1 5 0 dnegate test-dual
+-11 ' smallest-sm/rem catch test-single
+-11 ' smallest-fm/mod catch test-single
+
+."        Arithmetic errors"
+-10 ' divide-by-zero catch test-single
+-10 ' mod-by-zero catch test-single
+-11 ' add-overflow catch test-single
+-11 ' multiply-overflow catch test-single
+-11 ' value-overflow catch test-single
+9223372036854775807 big-value test-single
+-11 9223372036854775807 ' local-overflow catch swap drop test-single
+-11 ' loop-overflow catch test-single
+
+."        Wrapping arithmetic"
+wrap-on
+-9223372036854775808 value-overflow big-value test-single
+-9223372036854775808 9223372036854775807 1 + test-single
+9223372036854775807 -9223372036854775808 1 - test-single
+-4249290049419214848 21 fac test-single
+-9223372036854775808 -9223372036854775808 -1 / test-single
+0 -9223372036854775808 -1 mod test-single
+wrap-off
+
+."        Exceptions"
+-4 ' + catch test-single
+7 ' + catch -4 test-single 7 test-single \ the 7 is left alone
+' add-one catch -4 test-single
+7 after-literal-bad test-single
+8 after-divide-bad test-single
+-1 string-bad-freed test-single
+-99 ' throws-99 catch test-single
+-31 ' body-bad catch test-single
+-13 ' execute-bad catch test-single
+-13 ' catch-bad catch test-single
+-13 ' unset-handler catch test-single
+-12 ' to-bad catch test-single
+-12 ' defer-bad catch test-single
+1 catch-99 test-single
+42 0 ' no-throw catch test-dual
+1 0 nested-catch test-dual
+-2 1 ' abort-if catch swap drop test-single
+0 0 ' abort-if catch test-single
+3 7 2 divide-safely test-single
+0 7 0 divide-safely test-single
+-3 ' return-throw catch test-single
+-495 loop-catch test-single
+-1 ' abort catch test-single
+
+."        Strings"
+11 greeting swap drop test-single
+104 greeting drop c@ test-single
+5 counted-greeting c@ test-single
+5 counted-greeting count swap drop test-single
+0 greeting greeting compare test-single
+-1 s" abc" s" abd" compare test-single
+1 s" abd" s" abc" compare test-single
+-1 s" ab" s" abc" compare test-single
+-1 s" one" s" two" compare test-single
+-1 greeting s" world" search swap drop swap drop test-single
+0 greeting s" world" search drop s" world" compare test-single
+0 greeting s" xyz" search swap drop swap drop test-single
+0 greeting 6 /string s" world" compare test-single
+5 s" hello   " -trailing swap drop test-single
+0 greeting string-buffer swap cmove string-buffer 11 greeting compare test-single
+
+."        Escaped strings"
+6 escaped swap drop test-single
+9 escaped drop 1 + c@ test-single
+34 escaped drop 3 + c@ test-single
+92 escaped drop 5 + c@ test-single
+65 s\" \x41" drop c@ test-single
+10 s\" \n" drop c@ test-single
+27 s\" \e" drop c@ test-single
+2 s\" \m" swap drop test-single
+0 s\" a\"b" s\" a\qb" compare test-single
+
+."        Pictured numeric output"
+0 1250 money s" 12.50" compare test-single
+0 5 money s" 0.05" compare test-single
+0 -42 signed s" -42" compare test-single
+0 42 signed s" 42" compare test-single
+0 0 0 <# #s #> s" 0" compare test-single
+0 7 s>d <# # # # #> s" 007" compare test-single
+0 123 s>d <# s" ab" holds #s #> s" 123ab" compare test-single
+12 0 123 s>d <# # test-dual
+
+."        Number bases"
+255 $FF test-single
+10 #10 test-single
+10 %1010 test-single
+97 'a' test-single
+-1 $-1 test-single
+-1 $FFFFFFFFFFFFFFFF test-single
+5 +5 test-single
+5 #+5 test-single
+-9223372036854775808 #9223372036854775808 test-single
+1 1 9223372036854775808 fdrop test-single \ too big for a cell in decimal, so a float
+7 hex fade decimal test-single
+hex : cab ( -- n ) 3 ; decimal
+3 cab test-single
+255 hex FF decimal test-single
+16 hex base @ decimal test-single
+8 octal 10 decimal test-single
+5 binary 101 decimal test-single
+10 base @ test-single
+0 255 hex s>d <# #s #> decimal s" FF" compare test-single
+0 5 binary s>d <# #s #> decimal s" 101" compare test-single
+
+."        Definition order"
+' all-names ordered traverse-wordlist
+get-order ordered swap 1+ set-order
+' older test-single ' newer test-single ' newest test-single
+' first-name ordered traverse-wordlist ' newest test-single
+0 ' newest name>string s" newest" compare test-single
+previous
+
 ."        Constants"
 12 12 constant months months test-single \ a constant with the value 12
 
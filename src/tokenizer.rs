@@ -6,27 +6,45 @@ use crate::messages::Msg;
 use crate::reader::Reader;
 //use crate::utility;
 
-const BRANCHES: [&str; 10] = [
-    "if", "else", "then", "begin", "do", "loop", "leave", "until", "repeat", "+loop",
+const BRANCHES: [&str; 17] = [
+    "if", "else", "then", "endif", "begin", "while", "until", "repeat", "again", "do", "loop",
+    "leave", "+loop", "case", "of", "endof", "endcase",
 ];
-const FORWARDS: [(&str, &str); 7] = [
-    ("(", ")"),            // comment
-    ("s\"", "\""),         // stored string
-    (".\"", "\""),         // inline string print
-    ("see", " \t\n"),      // view word definition
-    ("variable", " \t\n"), // variable declaration
-    ("constant", " \t\n"), // constant declaration
-    ("\\", "\n"),          // comment to end of line
+const FORWARDS: [(&str, &str); 20] = [
+    ("(", ")"),             // comment
+    ("s\"", "\""),          // string, pushed as address and length
+    ("c\"", "\""),          // counted string, pushed as its address
+    ("s\\\"", "\""),        // string with escapes, pushed as address and length
+    (".\"", "\""),          // inline string print
+    (".\\\"", "\""),        // inline string print with escapes
+    ("abort\"", "\""),      // abort with a message
+    ("see", " \t\n"),       // view word definition
+    ("variable", " \t\n"),  // variable declaration
+    ("constant", " \t\n"),  // constant declaration
+    ("fvariable", " \t\n"), // floating point variable declaration
+    ("fconstant", " \t\n"), // floating point constant declaration
+    ("\\", "\n"),           // comment to end of line
+    ("[']", " \t\n"),       // execution token of the next word
+    ("is", " \t\n"),        // set a deferred word
+    ("action-of", " \t\n"), // get a deferred word's action
+    ("to", " \t\n"),        // change a value
+    ("+to", " \t\n"),       // add to a value
+    ("[char]", " \t\n"),    // character code of the next word
+    ("postpone", " \t\n"),  // compile the next word's compilation behaviour
 ];
 
 #[derive(Debug, Clone)]
 pub enum ForthToken {
-    Integer(i64),         // the token is an integer, stored here
-    Operator(String),     // the token is an operator
-    Branch(BranchInfo),   // branch
-    Forward(ForwardInfo), // a read_ahead token (string, comment etc.)
-    Float(f64),           // a floating point number
-    Empty,                // the line was empty
+    Integer(i64),                   // the token is an integer, stored here
+    Operator(String),               // the token is an operator
+    Branch(BranchInfo),             // branch
+    Forward(ForwardInfo),           // a read_ahead token (string, comment etc.)
+    Float(f64),                     // a floating point number
+    Call(usize),                    // a word bound at compile time, by execution token
+    Text(String, String, i64, i64), // a string compiled into a definition: s", s\" or c", the text as written, its address and length
+    Locals(Vec<String>, usize), // start a frame of locals: their names, and how many take values from the stack
+    Local(LocalInfo),           // read or change a local
+    Empty,                      // the line was empty
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +59,19 @@ impl ForwardInfo {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct LocalInfo {
+    pub word: String, // "" to push the local's value, "to" or "+to" to change it
+    pub name: String, // the local's name
+    pub slot: usize,  // its position in the frame
+}
+
+impl LocalInfo {
+    pub fn new(word: String, name: String, slot: usize) -> LocalInfo {
+        LocalInfo { word, name, slot }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BranchInfo {
     pub word: String,     // word name
@@ -64,6 +95,7 @@ pub struct Tokenizer {
     token_string: String,
     pub reader: Reader,
     branch_counter: usize,
+    base: u32, // the base numbers are read in
     msg: Msg,
 }
 
@@ -74,18 +106,28 @@ impl Tokenizer {
             token_string: String::new(),
             reader,
             branch_counter: 0,
+            base: 10,
             msg: Msg::new(),
         }
     }
 
+    pub fn set_base(&mut self, base: u32) {
+        self.base = base;
+    }
+
     pub fn clear(&mut self) {
         self.line.clear();
         self.token_string.clear();
     }
 
-    pub fn get_token(&mut self, current_stack: &String) -> Option<ForthToken> {
+    pub fn get_token(
+        &mut self,
+        current_stack: &String,
+        is_word: impl Fn(&str) -> bool,
+    ) -> Option<ForthToken> {
         // Return the token or None
-        // trim the token text off the front of self.line
+        // trim the token text off the front of self.line.
+        // is_word says whether text is a defined word, which is never read as a number
         let token_text = self.get_token_text(current_stack);
         match token_text {
             None => {
@@ -93,47 +135,47 @@ impl Tokenizer {
                 None
             }
             Some(text) => {
-                if is_integer(&text) {
-                    Some(ForthToken::Integer(text.parse().unwrap()))
-                } else if is_float(&text) {
-                    Some(ForthToken::Float(text.parse().unwrap()))
-                } else if BRANCHES.contains(&text.as_str()) {
-                    self.branch_counter += 1;
-                    Some(ForthToken::Branch(BranchInfo::new(
-                        text,
-                        0,
-                        self.branch_counter,
-                    )))
+                if let Some((_, terminator)) = FORWARDS.iter().find(|(word, _)| *word == text) {
+                    // it's a Forward: read the text that goes with it.
+                    // In s\" and .\" strings, \" doesn't end the string
+                    let escapes = text.ends_with("\\\"");
+                    let tail = self.read_until(terminator, escapes).unwrap_or_default();
+                    Some(ForthToken::Forward(ForwardInfo::new(text, tail)))
                 } else {
-                    // it's a Forward or an Operator
-                    for (word, terminator) in FORWARDS {
-                        if word == text {
-                            match self.read_until(terminator) {
-                                Some(remainder) => {
-                                    return Some(ForthToken::Forward(ForwardInfo::new(
-                                        text.to_owned(),
-                                        remainder.to_string(),
-                                    )))
-                                }
-                                None => {
-                                    return Some(ForthToken::Forward(ForwardInfo::new(
-                                        text.to_owned(),
-                                        "".to_owned(),
-                                    )));
-                                }
-                            }
-                        }
-                    }
-                    Some(ForthToken::Operator(text.to_owned()))
+                    let defined = is_word(&text);
+                    Some(self.word_token(text, defined))
                 }
             }
         }
     }
 
-    pub fn read_until(&mut self, terminator: &str) -> Option<String> {
+    pub fn word_token(&mut self, text: String, defined: bool) -> ForthToken {
+        // the token for a word that doesn't read ahead. Defined words are looked up first,
+        // so words like add and fac aren't taken for numbers in hex
+        if defined {
+            ForthToken::Operator(text)
+        } else if let Some(n) = parse_number(&text, self.base) {
+            ForthToken::Integer(n)
+        } else if self.base == 10 && is_float(&text) {
+            ForthToken::Float(text.parse().unwrap())
+        } else if BRANCHES.contains(&text.as_str()) {
+            self.branch_counter += 1;
+            ForthToken::Branch(BranchInfo::new(text, 0, self.branch_counter))
+        } else {
+            ForthToken::Operator(text)
+        }
+    }
+
+    pub fn get_word(&mut self) -> Option<String> {
+        // Read the next space delimited word, for words that parse their input when executed
+        self.get_token_text(&"".to_owned())
+    }
+
+    pub fn read_until(&mut self, terminator: &str, escapes: bool) -> Option<String> {
         // Read from the input stream, returning a string terminating in the first occurrence
-        // of  end_char.
+        // of  end_char. If escapes is true, a character after a backslash never ends the string.
         let mut multiline = false; // to drive the prompt
+        let mut escaped = false;
         let mut token_string = String::new();
         let mut chars_used = 0;
         loop {
@@ -150,7 +192,7 @@ impl Tokenizer {
                 }
             }
             'scan: for c in self.line.chars() {
-                if chars_used > 0 && terminator.contains(c) {
+                if chars_used > 0 && terminator.contains(c) && !escaped {
                     self.line = self.line[chars_used + 1..].to_string();
                     token_string.push(c);
                     return Some(token_string);
@@ -162,6 +204,7 @@ impl Tokenizer {
                     self.line.clear();
                     break 'scan;
                 } else {
+                    escaped = escapes && c == '\\' && !escaped;
                     token_string.push(c);
                     chars_used += 1;
                 }
@@ -214,8 +257,89 @@ impl Tokenizer {
     }
 }
 
-pub fn is_integer(s: &str) -> bool {
-    s.parse::<i64>().is_ok()
+pub fn is_forward(s: &str) -> bool {
+    // words that read the text following them when they are tokenized
+    FORWARDS.iter().any(|(word, _)| *word == s)
+}
+
+pub fn unescape(text: &str) -> Vec<u8> {
+    // the characters of text, with the Forth 2012 escapes replaced:
+    // \a \b \e \f \l \m \n \q \r \t \v \z \" \\ and \xHH for a character in hex
+    let mut bytes = Vec::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('a') => bytes.push(7),
+            Some('b') => bytes.push(8),
+            Some('e') => bytes.push(27),
+            Some('f') => bytes.push(12),
+            Some('l') | Some('n') => bytes.push(b'\n'),
+            Some('m') => bytes.extend_from_slice(b"\r\n"),
+            Some('q') | Some('"') => bytes.push(b'"'),
+            Some('r') => bytes.push(b'\r'),
+            Some('t') => bytes.push(b'\t'),
+            Some('v') => bytes.push(11),
+            Some('z') => bytes.push(0),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(byte) => bytes.push(byte),
+                    Err(_) => bytes.extend_from_slice(format!("\\x{hex}").as_bytes()),
+                }
+            }
+            Some(other) => {
+                // not an escape: keep the character
+                let mut buffer = [0; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buffer).as_bytes());
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+    bytes
+}
+
+pub fn parse_number(s: &str, base: u32) -> Option<i64> {
+    // a number in base, or with a prefix giving its base: $ for hex, # for decimal,
+    // % for binary, or a character in quotes like 'a'
+    let chars: Vec<char> = s.chars().collect();
+    if let ['\'', c, '\''] = chars[..] {
+        return Some(c as i64);
+    }
+    let (digits, base) = match chars.first() {
+        Some('$') => (&s[1..], 16),
+        Some('#') => (&s[1..], 10),
+        Some('%') => (&s[1..], 2),
+        _ => (s, base),
+    };
+    let prefixed = digits.len() < s.len();
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, digits.strip_prefix('+').unwrap_or(digits)),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(base)) {
+        return None;
+    }
+    // outside plain decimal, numbers too big for a signed cell are read as unsigned,
+    // so masks like $FFFFFFFFFFFFFFFF work. Big decimal numbers are left to be read as floats.
+    let n = u64::from_str_radix(digits, base).ok()?;
+    let limit = if negative {
+        i64::MIN.unsigned_abs()
+    } else {
+        i64::MAX as u64
+    };
+    if n > limit && base == 10 && !prefixed {
+        return None;
+    }
+    Some(if negative {
+        (n as i64).wrapping_neg()
+    } else {
+        n as i64
+    })
 }
 
 pub fn is_float(s: &str) -> bool {